@@ -4,5 +4,9 @@ pub mod increment;
 pub mod interval;
 pub mod operation;
 pub mod position;
+#[cfg(test)]
+pub mod property;
+pub mod random;
+pub mod threads;
 pub mod three_d;
 pub mod vector_chunks;
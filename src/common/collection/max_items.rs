@@ -14,16 +14,44 @@ where
     where
         I: Iterator<Item = Self>,
     {
-        let mut top_items: BTreeSet<T> = BTreeSet::new();
+        let mut top_items: TopItems<T> = TopItems::new(count);
 
         for item in iter {
-            top_items.insert(item);
-            if top_items.len() > count {
-                top_items.pop_first();
-            }
+            top_items.push(item);
         }
 
-        top_items.into_iter().rev().collect::<Vec<T>>()
+        top_items.into_vec()
+    }
+}
+
+/// Keeps the largest `count` distinct items pushed so far, so that the memory used does not grow
+/// with the number of items.
+pub struct TopItems<T> {
+    count: usize,
+    items: BTreeSet<T>,
+}
+
+impl<T> TopItems<T>
+where
+    T: Ord,
+{
+    pub fn new(count: usize) -> TopItems<T> {
+        TopItems {
+            count,
+            items: BTreeSet::new(),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.insert(item);
+        if self.items.len() > self.count {
+            self.items.pop_first();
+        }
+    }
+
+    /// Returns the items from largest to smallest.
+    pub fn into_vec(self) -> Vec<T> {
+        self.items.into_iter().rev().collect::<Vec<T>>()
     }
 }
 
@@ -177,3 +177,30 @@ where
         union
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::property::{self, Integers};
+
+    #[test]
+    fn overlap_should_be_commutative() {
+        // Intervals are generated as a start and a length
+        property::check(
+            &(
+                (Integers(-100..100), Integers(0..50)),
+                (Integers(-100..100), Integers(0..50)),
+            ),
+            |((start_1, length_1), (start_2, length_2))| {
+                let (interval_1, interval_2) = match (
+                    Interval::build(*start_1, start_1 + length_1),
+                    Interval::build(*start_2, start_2 + length_2),
+                ) {
+                    (Ok(interval_1), Ok(interval_2)) => (interval_1, interval_2),
+                    _ => return false,
+                };
+                interval_1.overlap(interval_2) == interval_2.overlap(interval_1)
+            },
+        );
+    }
+}
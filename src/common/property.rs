@@ -0,0 +1,348 @@
+use super::random::Random;
+use std::{fmt::Debug, ops::Range};
+
+/// The number of generated cases that `check` tries before accepting a property.
+const CASE_COUNT: u64 = 100;
+/// The maximum number of shrinking steps, in case shrinking does not converge quickly.
+const SHRINK_LIMIT: usize = 1000;
+
+/// Generates random values from a seeded `Random`, and knows how to simplify them.
+pub trait Generator {
+    type Item: Clone + Debug;
+
+    fn generate(&self, random: &mut Random) -> Self::Item;
+
+    /// Returns simpler variants of the given item, which are tried in order while shrinking.
+    fn shrink(&self, item: &Self::Item) -> Vec<Self::Item>;
+}
+
+/// Checks that the property holds for generated values. If it does not, the failing value is
+/// shrunk to a simpler value for which the property also fails, and the check panics with it.
+pub fn check<G>(generator: &G, property: impl Fn(&G::Item) -> bool)
+where
+    G: Generator,
+{
+    if let Some((case, item, shrunk)) = find_failure(generator, &property) {
+        panic!(
+            "property failed for {:?} (shrunk from {:?}, case {})",
+            shrunk, item, case
+        );
+    }
+}
+
+/// Checks that a fast implementation gives the same answers as a reference implementation on
+/// generated values, and panics with both answers for the simplest value on which they differ.
+pub fn check_against_reference<G, T>(
+    generator: &G,
+    fast: impl Fn(&G::Item) -> T,
+    reference: impl Fn(&G::Item) -> T,
+) where
+    G: Generator,
+    T: Debug + PartialEq,
+{
+    if let Some((case, _, shrunk)) = find_failure(generator, &|item| fast(item) == reference(item))
+    {
+        panic!(
+            "answer {:?} differs from reference answer {:?} for {:?} (case {})",
+            fast(&shrunk),
+            reference(&shrunk),
+            shrunk,
+            case
+        );
+    }
+}
+
+fn find_failure<G>(
+    generator: &G,
+    property: &impl Fn(&G::Item) -> bool,
+) -> Option<(u64, G::Item, G::Item)>
+where
+    G: Generator,
+{
+    for case in 0..CASE_COUNT {
+        let mut random = Random::new(case);
+        let item = generator.generate(&mut random);
+        if !property(&item) {
+            let shrunk = shrink_failure(generator, property, item.clone());
+            return Some((case, item, shrunk));
+        }
+    }
+    None
+}
+
+fn shrink_failure<G>(generator: &G, property: &impl Fn(&G::Item) -> bool, item: G::Item) -> G::Item
+where
+    G: Generator,
+{
+    let mut item = item;
+    for _ in 0..SHRINK_LIMIT {
+        match generator
+            .shrink(&item)
+            .into_iter()
+            .find(|candidate| !property(candidate))
+        {
+            Some(candidate) => item = candidate,
+            None => break,
+        }
+    }
+    item
+}
+
+/// Generates integers in a range, shrinking towards 0 (or the closest value to 0 in the range).
+pub struct Integers(pub Range<i64>);
+
+impl Generator for Integers {
+    type Item = i64;
+
+    fn generate(&self, random: &mut Random) -> i64 {
+        random.range(self.0.clone())
+    }
+
+    fn shrink(&self, item: &i64) -> Vec<i64> {
+        let target = 0.clamp(self.0.start, (self.0.end - 1).max(self.0.start));
+        let mut candidates: Vec<i64> = Vec::new();
+        let mut difference = *item - target;
+        while difference != 0 {
+            candidates.push(*item - difference);
+            difference /= 2;
+        }
+        candidates
+    }
+}
+
+/// Generates puzzle inputs with one of the `_generate` functions, using a size from the given
+/// range. Inputs are not shrunk, since part of a valid input need not be a valid input.
+pub struct PuzzleInputs {
+    pub generate: fn(&mut Random, usize) -> String,
+    pub sizes: Range<usize>,
+}
+
+impl Generator for PuzzleInputs {
+    type Item = String;
+
+    fn generate(&self, random: &mut Random) -> String {
+        let size = self.sizes.start + random.below(self.sizes.len());
+        (self.generate)(random, size)
+    }
+
+    fn shrink(&self, _: &String) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Generates one of the given items, shrinking towards the items that come first.
+pub struct Choices<T>(pub Vec<T>);
+
+impl<T> Generator for Choices<T>
+where
+    T: Clone + Debug + PartialEq,
+{
+    type Item = T;
+
+    fn generate(&self, random: &mut Random) -> T {
+        random.choose(&self.0).clone()
+    }
+
+    fn shrink(&self, item: &T) -> Vec<T> {
+        self.0
+            .iter()
+            .take_while(|choice| *choice != item)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Generates vectors with a length in the given range, shrinking by removing elements first, and
+/// shrinking single elements after that.
+pub struct Vectors<G> {
+    pub element: G,
+    pub lengths: Range<usize>,
+}
+
+impl<G> Generator for Vectors<G>
+where
+    G: Generator,
+{
+    type Item = Vec<G::Item>;
+
+    fn generate(&self, random: &mut Random) -> Vec<G::Item> {
+        let length = self.lengths.start + random.below(self.lengths.len());
+        (0..length).map(|_| self.element.generate(random)).collect()
+    }
+
+    fn shrink(&self, item: &Vec<G::Item>) -> Vec<Vec<G::Item>> {
+        let mut candidates: Vec<Vec<G::Item>> = Vec::new();
+
+        let mut removed_count = item.len().saturating_sub(self.lengths.start);
+        while removed_count > 0 {
+            for start in (0..=(item.len() - removed_count)).step_by(removed_count) {
+                let mut candidate = item[..start].to_vec();
+                candidate.extend_from_slice(&item[(start + removed_count)..]);
+                candidates.push(candidate);
+            }
+            removed_count /= 2;
+        }
+
+        for (index, element) in item.iter().enumerate() {
+            for shrunk in self.element.shrink(element) {
+                let mut candidate = item.clone();
+                candidate[index] = shrunk;
+                candidates.push(candidate);
+            }
+        }
+
+        candidates
+    }
+}
+
+/// Generates strings with characters from the given alphabet, shrinking like vectors of
+/// characters towards the start of the alphabet.
+pub struct Strings(Vectors<Choices<char>>);
+
+impl Strings {
+    pub fn new(alphabet: &str, lengths: Range<usize>) -> Strings {
+        Strings(Vectors {
+            element: Choices(alphabet.chars().collect()),
+            lengths,
+        })
+    }
+}
+
+impl Generator for Strings {
+    type Item = String;
+
+    fn generate(&self, random: &mut Random) -> String {
+        self.0.generate(random).into_iter().collect()
+    }
+
+    fn shrink(&self, item: &String) -> Vec<String> {
+        self.0
+            .shrink(&item.chars().collect())
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<A, B> Generator for (A, B)
+where
+    A: Generator,
+    B: Generator,
+{
+    type Item = (A::Item, B::Item);
+
+    fn generate(&self, random: &mut Random) -> Self::Item {
+        (self.0.generate(random), self.1.generate(random))
+    }
+
+    fn shrink(&self, item: &Self::Item) -> Vec<Self::Item> {
+        let (a, b) = item;
+        let mut candidates: Vec<Self::Item> = Vec::new();
+        candidates.extend(self.0.shrink(a).into_iter().map(|a| (a, b.clone())));
+        candidates.extend(self.1.shrink(b).into_iter().map(|b| (a.clone(), b)));
+        candidates
+    }
+}
+
+impl<A, B, C> Generator for (A, B, C)
+where
+    A: Generator,
+    B: Generator,
+    C: Generator,
+{
+    type Item = (A::Item, B::Item, C::Item);
+
+    fn generate(&self, random: &mut Random) -> Self::Item {
+        (
+            self.0.generate(random),
+            self.1.generate(random),
+            self.2.generate(random),
+        )
+    }
+
+    fn shrink(&self, item: &Self::Item) -> Vec<Self::Item> {
+        let (a, b, c) = item;
+        let mut candidates: Vec<Self::Item> = Vec::new();
+        candidates.extend(
+            self.0
+                .shrink(a)
+                .into_iter()
+                .map(|a| (a, b.clone(), c.clone())),
+        );
+        candidates.extend(
+            self.1
+                .shrink(b)
+                .into_iter()
+                .map(|b| (a.clone(), b, c.clone())),
+        );
+        candidates.extend(
+            self.2
+                .shrink(c)
+                .into_iter()
+                .map(|c| (a.clone(), b.clone(), c)),
+        );
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn should_accept_valid_property() {
+        check(&Integers(-100..100), |x| x * 2 == x + x);
+    }
+
+    #[test]
+    fn should_shrink_integers_to_boundary() {
+        let result = panic::catch_unwind(|| check(&Integers(0..1000), |x| *x < 10));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("property failed for 10 "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn should_shrink_vectors_to_minimal_counterexample() {
+        let generator = Vectors {
+            element: Integers(0..100),
+            lengths: 0..20,
+        };
+        let result = panic::catch_unwind(|| check(&generator, |xs| xs.iter().sum::<i64>() < 50));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("property failed for [50] "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn should_shrink_strings_to_minimal_counterexample() {
+        let generator = Strings::new("abc", 0..20);
+        let result = panic::catch_unwind(|| check(&generator, |s| !s.contains('c')));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("property failed for \"c\" "),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn should_report_simplest_difference_from_reference() {
+        let result = panic::catch_unwind(|| {
+            check_against_reference(&Integers(-100..100), |x| x.abs(), |x| *x)
+        });
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(
+            message.starts_with("answer 1 differs from reference answer -1 for -1 "),
+            "{}",
+            message
+        );
+    }
+}
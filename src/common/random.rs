@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+/// A small xorshift pseudo-random number generator, so that generated inputs can be reproduced from
+/// a seed without pulling in external crates.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // Scramble the seed (splitmix64), so that small and zero seeds still give a usable state
+        let mut state = seed.wrapping_add(0x9e3779b97f4a7c15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94d049bb133111eb);
+        state ^= state >> 31;
+        if state == 0 {
+            state = 0x9e3779b97f4a7c15;
+        }
+        Random { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    /// Returns a value in `0..bound`, or 0 if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            (self.next_u64() % (bound as u64)) as usize
+        }
+    }
+
+    /// Returns a value in the given range, or its start if the range is empty.
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        if range.end <= range.start {
+            range.start
+        } else {
+            let width = range.end.abs_diff(range.start);
+            range.start.wrapping_add((self.next_u64() % width) as i64)
+        }
+    }
+
+    /// Returns true with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.below(index + 1);
+            items.swap(index, other);
+        }
+    }
+}
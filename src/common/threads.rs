@@ -0,0 +1,124 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{cell::Cell, thread};
+
+static THREAD_COUNT: AtomicUsize = AtomicUsize::new(1);
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    /// Replaces the process-wide thread count on this thread. Worker threads set it to 1, so that
+    /// nested work does not spawn even more threads.
+    static LOCAL_THREAD_COUNT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Sets the number of threads that independent work may be spread over (at least 1), for the
+/// whole process.
+pub fn set_thread_count(count: usize) {
+    THREAD_COUNT.store(count.max(1), Ordering::Relaxed);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn thread_count() -> usize {
+    LOCAL_THREAD_COUNT
+        .with(|local| local.get())
+        .unwrap_or_else(|| THREAD_COUNT.load(Ordering::Relaxed))
+}
+
+/// Runs `f` with work spread over the given number of threads (at least 1), without affecting
+/// other threads. Tests use this rather than `set_thread_count`, as they run side by side.
+#[cfg(all(test, not(target_arch = "wasm32")))]
+pub fn with_thread_count<R>(count: usize, f: impl FnOnce() -> R) -> R {
+    let previous = LOCAL_THREAD_COUNT.with(|local| local.replace(Some(count.max(1))));
+    let result = f();
+    LOCAL_THREAD_COUNT.with(|local| local.set(previous));
+    result
+}
+
+// Threads are not available in wasm, so everything runs on a single thread there
+#[cfg(target_arch = "wasm32")]
+pub fn thread_count() -> usize {
+    1
+}
+
+/// Maps the items, spreading them over up to `thread_count()` threads. The results are in the same
+/// order as the items, regardless of the number of threads.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parallel_map<T, U, F>(items: Vec<T>, f: F) -> Vec<U>
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Sync,
+{
+    let thread_count = thread_count().min(items.len());
+    if thread_count <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(thread_count);
+    let mut chunks: Vec<Vec<T>> = Vec::new();
+    let mut items = items.into_iter().peekable();
+    while items.peek().is_some() {
+        chunks.push(items.by_ref().take(chunk_size).collect());
+    }
+
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    LOCAL_THREAD_COUNT.with(|local| local.set(Some(1)));
+                    chunk.into_iter().map(f).collect::<Vec<U>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn parallel_map<T, U, F>(items: Vec<T>, f: F) -> Vec<U>
+where
+    F: Fn(T) -> U,
+{
+    items.into_iter().map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_map_should_keep_order() {
+        let results = with_thread_count(4, || {
+            parallel_map((0..10).collect(), |x: usize| (x * x, thread_count()))
+        });
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|(square, _)| *square)
+                .collect::<Vec<usize>>(),
+            vec![0, 1, 4, 9, 16, 25, 36, 49, 64, 81]
+        );
+        assert!(results.iter().all(|(_, count)| *count == 1));
+    }
+
+    #[test]
+    fn thread_count_should_only_change_on_this_thread() {
+        let (inside, other) = with_thread_count(3, || {
+            let other = thread::spawn(thread_count).join().unwrap();
+            (thread_count(), other)
+        });
+
+        assert_eq!(3, inside);
+        assert_eq!(THREAD_COUNT.load(Ordering::Relaxed), other);
+    }
+}
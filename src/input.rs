@@ -1,20 +1,28 @@
+pub mod input_source;
 mod match_args;
 pub mod puzzle_input;
 pub mod puzzle_part;
 mod puzzle_type;
+pub mod report_input;
 
 use std::{
     error::Error,
     fmt::{self, Debug, Display, Formatter},
-    fs, io,
+    iter,
 };
 
-use crate::{input::puzzle_input::PuzzleInput, parse::error::ParseContentsError};
+use crate::{
+    common::{random::Random, threads},
+    input::puzzle_input::PuzzleInput,
+    parse::error::ParseContentsError,
+};
 
 use self::{
+    input_source::{CacheDirectory, InputSource, LocalFiles, ReadInputError},
     match_args::{MatchArgs, MatchArgsError, MatchArgsIterator},
     puzzle_part::{ParsePuzzlePartError, PuzzlePart},
     puzzle_type::{ParsePuzzleTypeError, PuzzleType},
+    report_input::{ReportError, ReportInput},
 };
 
 pub enum ParsePuzzleArgsError {
@@ -22,6 +30,14 @@ pub enum ParsePuzzleArgsError {
     InvalidPuzzleType(String),
     MissingPuzzlePart,
     InvalidPuzzlePart(String),
+    MissingSeed,
+    InvalidSeed(String),
+    MissingSize,
+    InvalidSize(String),
+    MissingThreadCount,
+    InvalidThreadCount(String),
+    MissingProfile,
+    MissingReportMode,
 }
 
 impl Display for ParsePuzzleArgsError {
@@ -31,6 +47,14 @@ impl Display for ParsePuzzleArgsError {
             Self::MissingPuzzlePart => write!(f, "missing puzzle part"),
             Self::InvalidPuzzleType(error) => write!(f, "invalid puzzle type '{}'", error),
             Self::InvalidPuzzlePart(error) => write!(f, "invalid puzzle part '{}'", error),
+            Self::MissingSeed => write!(f, "missing seed"),
+            Self::InvalidSeed(error) => write!(f, "invalid seed '{}'", error),
+            Self::MissingSize => write!(f, "missing size"),
+            Self::InvalidSize(error) => write!(f, "invalid size '{}'", error),
+            Self::MissingThreadCount => write!(f, "missing thread count"),
+            Self::InvalidThreadCount(error) => write!(f, "invalid thread count '{}'", error),
+            Self::MissingProfile => write!(f, "missing profile"),
+            Self::MissingReportMode => write!(f, "missing report mode"),
         }
     }
 }
@@ -44,27 +68,33 @@ impl Debug for ParsePuzzleArgsError {
 impl Error for ParsePuzzleArgsError {}
 
 pub enum RunSolutionError {
-    FileReadError {
-        file_name: String,
-        error: io::Error,
-    },
+    InputReadError(ReadInputError),
     FileParseError {
         file_name: String,
         error: ParseContentsError,
     },
+    SolutionsFailed(usize),
+    NoReports(String),
+    ReportFailed {
+        file_name: String,
+        error: ReportError,
+    },
 }
 
 impl Display for RunSolutionError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Self::FileReadError {
-                file_name,
-                error: _,
-            } => write!(f, "error while reading '{}'", file_name),
+            Self::InputReadError(error) => write!(f, "{}", error),
             Self::FileParseError {
                 file_name,
                 error: _,
             } => write!(f, "error parsing contents of '{}'", file_name),
+            Self::SolutionsFailed(count) => write!(f, "{} solutions failed", count),
+            Self::NoReports(puzzle) => write!(f, "'{}' has no report modes", puzzle),
+            Self::ReportFailed {
+                file_name,
+                error: _,
+            } => write!(f, "error reporting on '{}'", file_name),
         }
     }
 }
@@ -78,11 +108,14 @@ impl Debug for RunSolutionError {
 impl Error for RunSolutionError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            Self::FileReadError {
+            Self::InputReadError(error) => error.source(),
+            Self::FileParseError {
                 file_name: _,
                 error,
             } => Some(error),
-            Self::FileParseError {
+            Self::SolutionsFailed(_) => None,
+            Self::NoReports(_) => None,
+            Self::ReportFailed {
                 file_name: _,
                 error,
             } => Some(error),
@@ -127,19 +160,259 @@ impl PuzzleArgs {
         args.next_match()
     }
 
-    pub fn run_solution(&self) -> Result<(), RunSolutionError> {
-        let file_name = format!("input/{}.txt", self.puzzle_type.file_name());
-        let file_contents =
-            fs::read_to_string(&file_name).map_err(|error| RunSolutionError::FileReadError {
-                file_name: file_name.clone(),
-                error,
-            })?;
-        let output = self.puzzle_type.solver()(PuzzleInput {
+    pub fn run_solution(&self, source: &dyn InputSource) -> Result<(), RunSolutionError> {
+        let output = self.solve(source)?;
+        println!("The answer is:\n{}", output);
+        Ok(())
+    }
+
+    fn solve(&self, source: &dyn InputSource) -> Result<String, RunSolutionError> {
+        let file_name = source.location(self.puzzle_type);
+        let output = match self.puzzle_type.streaming_solver() {
+            Some(streaming_solver) => {
+                let reader = source
+                    .open(self.puzzle_type)
+                    .map_err(RunSolutionError::InputReadError)?;
+                streaming_solver(reader, self.puzzle_part)
+            }
+            None => {
+                let file_contents = source
+                    .read(self.puzzle_type)
+                    .map_err(RunSolutionError::InputReadError)?;
+                self.puzzle_type.solver()(PuzzleInput {
+                    file_contents,
+                    puzzle_part: self.puzzle_part,
+                })
+            }
+        };
+        output.map_err(|error| RunSolutionError::FileParseError { file_name, error })
+    }
+}
+
+pub struct GenerateArgs {
+    puzzle_type: PuzzleType,
+    seed: u64,
+    size: usize,
+}
+
+impl MatchArgs for GenerateArgs {
+    type Err = ParsePuzzleArgsError;
+
+    fn match_args(args: &mut impl Iterator<Item = String>) -> Result<Self, Self::Err> {
+        let puzzle_type = args.next_match::<PuzzleType>().map_err(|err| match err {
+            MatchArgsError::ParseError(ParsePuzzleTypeError::InvalidValue(value)) => {
+                Self::Err::InvalidPuzzleType(value)
+            }
+            MatchArgsError::EndOfArgsError => Self::Err::MissingPuzzleType,
+        })?;
+        let seed = match args.next() {
+            Some(seed) => seed
+                .parse::<u64>()
+                .map_err(|_| Self::Err::InvalidSeed(seed)),
+            None => Err(Self::Err::MissingSeed),
+        }?;
+        let size = match args.next() {
+            Some(size) => size
+                .parse::<usize>()
+                .map_err(|_| Self::Err::InvalidSize(size)),
+            None => Err(Self::Err::MissingSize),
+        }?;
+
+        Ok(GenerateArgs {
+            puzzle_type,
+            seed,
+            size,
+        })
+    }
+}
+
+impl GenerateArgs {
+    pub fn run_generator(&self) {
+        let mut random = Random::new(self.seed);
+        let output = self.puzzle_type.generator()(&mut random, self.size);
+        print!("{}", output);
+    }
+}
+
+pub struct ReportArgs {
+    puzzle_type: PuzzleType,
+    mode: String,
+    arguments: Vec<String>,
+}
+
+impl MatchArgs for ReportArgs {
+    type Err = ParsePuzzleArgsError;
+
+    fn match_args(args: &mut impl Iterator<Item = String>) -> Result<Self, Self::Err> {
+        let puzzle_type = args.next_match::<PuzzleType>().map_err(|err| match err {
+            MatchArgsError::ParseError(ParsePuzzleTypeError::InvalidValue(value)) => {
+                Self::Err::InvalidPuzzleType(value)
+            }
+            MatchArgsError::EndOfArgsError => Self::Err::MissingPuzzleType,
+        })?;
+        let mode = args.next().ok_or(Self::Err::MissingReportMode)?;
+
+        Ok(ReportArgs {
+            puzzle_type,
+            mode,
+            arguments: args.collect(),
+        })
+    }
+}
+
+impl ReportArgs {
+    pub fn run_report(&self, source: &dyn InputSource) -> Result<(), RunSolutionError> {
+        let reporter = self
+            .puzzle_type
+            .reporter()
+            .ok_or_else(|| RunSolutionError::NoReports(self.puzzle_type.file_name().to_string()))?;
+        let file_name = source.location(self.puzzle_type);
+        let file_contents = source
+            .read(self.puzzle_type)
+            .map_err(RunSolutionError::InputReadError)?;
+        let output = reporter(ReportInput {
+            mode: self.mode.clone(),
+            arguments: self.arguments.clone(),
             file_contents,
-            puzzle_part: self.puzzle_part,
         })
-        .map_err(|error| RunSolutionError::FileParseError { file_name, error })?;
-        println!("The answer is:\n{}", output);
+        .map_err(|error| RunSolutionError::ReportFailed { file_name, error })?;
+        print!("{}", output);
+        Ok(())
+    }
+}
+
+enum PuzzleAction {
+    Solve(PuzzleArgs),
+    SolveAll,
+    Generate(GenerateArgs),
+    Report(ReportArgs),
+}
+
+impl MatchArgs for PuzzleAction {
+    type Err = ParsePuzzleArgsError;
+
+    fn match_args(args: &mut impl Iterator<Item = String>) -> Result<Self, Self::Err> {
+        match args.next() {
+            Some(command) if command == "all" => Ok(Self::SolveAll),
+            Some(command) if command == "generate" => Ok(Self::Generate(args.next_match()?)),
+            Some(command) if command == "report" => Ok(Self::Report(args.next_match()?)),
+            Some(puzzle_type) => Ok(Self::Solve(
+                iter::once(puzzle_type).chain(args).next_match()?,
+            )),
+            None => Err(Self::Err::MissingPuzzleType),
+        }
+    }
+}
+
+pub struct PuzzleCommand {
+    action: PuzzleAction,
+    thread_count: usize,
+    profile: Option<String>,
+}
+
+impl MatchArgs for PuzzleCommand {
+    type Err = ParsePuzzleArgsError;
+
+    fn match_args(args: &mut impl Iterator<Item = String>) -> Result<Self, Self::Err> {
+        // Options may appear anywhere, so they are taken out before matching the action
+        let mut thread_count: usize = 1;
+        let mut profile: Option<String> = None;
+        let mut action_args: Vec<String> = Vec::new();
+        while let Some(arg) = args.next() {
+            if arg == "--threads" {
+                thread_count = match args.next() {
+                    Some(count) => match count.parse::<usize>() {
+                        Ok(count) if count > 0 => Ok(count),
+                        _ => Err(Self::Err::InvalidThreadCount(count)),
+                    },
+                    None => Err(Self::Err::MissingThreadCount),
+                }?;
+            } else if arg == "--profile" {
+                profile = Some(args.next().ok_or(Self::Err::MissingProfile)?);
+            } else {
+                action_args.push(arg);
+            }
+        }
+
+        Ok(PuzzleCommand {
+            action: action_args.into_iter().next_match()?,
+            thread_count,
+            profile,
+        })
+    }
+}
+
+impl PuzzleCommand {
+    pub fn build(
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<PuzzleCommand, ParsePuzzleArgsError> {
+        args.next();
+        args.next_match()
+    }
+
+    pub fn run(&self) -> Result<(), RunSolutionError> {
+        threads::set_thread_count(self.thread_count);
+        let source: Box<dyn InputSource> = match &self.profile {
+            Some(profile) => Box::new(CacheDirectory::new("cache", profile)),
+            None => Box::new(LocalFiles::new("input")),
+        };
+        match &self.action {
+            PuzzleAction::Solve(puzzle_args) => puzzle_args.run_solution(source.as_ref()),
+            PuzzleAction::SolveAll => run_all_solutions(source.as_ref()),
+            PuzzleAction::Report(report_args) => report_args.run_report(source.as_ref()),
+            PuzzleAction::Generate(generate_args) => {
+                generate_args.run_generator();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn run_all_solutions(source: &dyn InputSource) -> Result<(), RunSolutionError> {
+    let all_puzzle_args = PuzzleType::all()
+        .into_iter()
+        .flat_map(|puzzle_type| {
+            [PuzzlePart::Part1, PuzzlePart::Part2].map(|puzzle_part| PuzzleArgs {
+                puzzle_type,
+                puzzle_part,
+            })
+        })
+        .collect::<Vec<PuzzleArgs>>();
+    let outputs = threads::parallel_map(all_puzzle_args.iter().collect(), |puzzle_args| {
+        puzzle_args.solve(source)
+    });
+
+    let mut failed_count: usize = 0;
+    for (puzzle_args, output) in all_puzzle_args.iter().zip(outputs) {
+        let part_number = match puzzle_args.puzzle_part {
+            PuzzlePart::Part1 => 1,
+            PuzzlePart::Part2 => 2,
+        };
+        match output {
+            Ok(output) => println!(
+                "{} part {}:\n{}",
+                puzzle_args.puzzle_type.file_name(),
+                part_number,
+                output
+            ),
+            Err(error) => {
+                failed_count += 1;
+                eprintln!(
+                    "{} part {}: {}",
+                    puzzle_args.puzzle_type.file_name(),
+                    part_number,
+                    error
+                );
+                if let Some(source) = error.source() {
+                    eprintln!("{}", source);
+                }
+            }
+        }
+    }
+
+    if failed_count > 0 {
+        Err(RunSolutionError::SolutionsFailed(failed_count))
+    } else {
         Ok(())
     }
 }
@@ -0,0 +1,241 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Cursor},
+    path::PathBuf,
+};
+
+use super::puzzle_type::PuzzleType;
+
+pub enum ReadInputError {
+    Missing { puzzle: String, location: String },
+    Unreadable { location: String, error: io::Error },
+}
+
+impl Display for ReadInputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { puzzle, location } => {
+                write!(f, "no input for '{}': looked for '{}'", puzzle, location)
+            }
+            Self::Unreadable { location, error: _ } => {
+                write!(f, "error while reading '{}'", location)
+            }
+        }
+    }
+}
+
+impl Debug for ReadInputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for ReadInputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Missing {
+                puzzle: _,
+                location: _,
+            } => None,
+            Self::Unreadable { location: _, error } => Some(error),
+        }
+    }
+}
+
+/// A place to get puzzle inputs from.
+pub trait InputSource: Sync {
+    /// Describes where the input of the puzzle is looked up, for use in messages.
+    fn location(&self, puzzle_type: PuzzleType) -> String;
+
+    fn read(&self, puzzle_type: PuzzleType) -> Result<String, ReadInputError>;
+
+    /// Opens the input of the puzzle for reading it bit by bit, for puzzles that can be solved
+    /// without holding the whole input in memory.
+    fn open(&self, puzzle_type: PuzzleType) -> Result<Box<dyn BufRead>, ReadInputError>;
+}
+
+/// Inputs stored as `<directory>/<puzzle type>.txt`.
+pub struct LocalFiles {
+    directory: PathBuf,
+}
+
+impl LocalFiles {
+    pub fn new(directory: impl Into<PathBuf>) -> LocalFiles {
+        LocalFiles {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl InputSource for LocalFiles {
+    fn location(&self, puzzle_type: PuzzleType) -> String {
+        self.directory
+            .join(format!("{}.txt", puzzle_type.file_name()))
+            .display()
+            .to_string()
+    }
+
+    fn read(&self, puzzle_type: PuzzleType) -> Result<String, ReadInputError> {
+        read_file(puzzle_type, self.location(puzzle_type))
+    }
+
+    fn open(&self, puzzle_type: PuzzleType) -> Result<Box<dyn BufRead>, ReadInputError> {
+        open_file(puzzle_type, self.location(puzzle_type))
+    }
+}
+
+/// Inputs of several accounts, stored as `<directory>/<profile>/<year>/day<day>.txt`.
+pub struct CacheDirectory {
+    directory: PathBuf,
+    profile: String,
+}
+
+impl CacheDirectory {
+    const YEAR: usize = 2022;
+
+    pub fn new(directory: impl Into<PathBuf>, profile: &str) -> CacheDirectory {
+        CacheDirectory {
+            directory: directory.into(),
+            profile: profile.to_string(),
+        }
+    }
+}
+
+impl InputSource for CacheDirectory {
+    fn location(&self, puzzle_type: PuzzleType) -> String {
+        self.directory
+            .join(&self.profile)
+            .join(Self::YEAR.to_string())
+            .join(format!("day{:02}.txt", puzzle_type.day()))
+            .display()
+            .to_string()
+    }
+
+    fn read(&self, puzzle_type: PuzzleType) -> Result<String, ReadInputError> {
+        read_file(puzzle_type, self.location(puzzle_type))
+    }
+
+    fn open(&self, puzzle_type: PuzzleType) -> Result<Box<dyn BufRead>, ReadInputError> {
+        open_file(puzzle_type, self.location(puzzle_type))
+    }
+}
+
+/// Inputs kept in memory, mostly for tests.
+#[derive(Default)]
+pub struct InMemory {
+    inputs: HashMap<PuzzleType, String>,
+}
+
+impl InMemory {
+    pub fn new() -> InMemory {
+        InMemory::default()
+    }
+
+    pub fn with(mut self, puzzle_type: PuzzleType, input: &str) -> InMemory {
+        self.inputs.insert(puzzle_type, input.to_string());
+        self
+    }
+}
+
+impl InputSource for InMemory {
+    fn location(&self, puzzle_type: PuzzleType) -> String {
+        format!("memory/{}", puzzle_type.file_name())
+    }
+
+    fn read(&self, puzzle_type: PuzzleType) -> Result<String, ReadInputError> {
+        match self.inputs.get(&puzzle_type) {
+            Some(input) => Ok(input.clone()),
+            None => Err(ReadInputError::Missing {
+                puzzle: puzzle_type.file_name().to_string(),
+                location: self.location(puzzle_type),
+            }),
+        }
+    }
+
+    fn open(&self, puzzle_type: PuzzleType) -> Result<Box<dyn BufRead>, ReadInputError> {
+        let input = self.read(puzzle_type)?;
+        Ok(Box::new(Cursor::new(input.into_bytes())))
+    }
+}
+
+fn read_file(puzzle_type: PuzzleType, location: String) -> Result<String, ReadInputError> {
+    fs::read_to_string(&location).map_err(|error| file_error(puzzle_type, location, error))
+}
+
+fn open_file(
+    puzzle_type: PuzzleType,
+    location: String,
+) -> Result<Box<dyn BufRead>, ReadInputError> {
+    match File::open(&location) {
+        Ok(file) => Ok(Box::new(BufReader::new(file))),
+        Err(error) => Err(file_error(puzzle_type, location, error)),
+    }
+}
+
+fn file_error(puzzle_type: PuzzleType, location: String, error: io::Error) -> ReadInputError {
+    match error.kind() {
+        io::ErrorKind::NotFound => ReadInputError::Missing {
+            puzzle: puzzle_type.file_name().to_string(),
+            location,
+        },
+        _ => ReadInputError::Unreadable { location, error },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn in_memory_should_read_stored_input() -> Result<(), Box<dyn Error>> {
+        let source = InMemory::new().with(PuzzleType::RopeBridge, "R 4\n");
+
+        assert_eq!("R 4\n", source.read(PuzzleType::RopeBridge)?);
+        Ok(())
+    }
+
+    #[test]
+    fn in_memory_should_open_stored_input() -> Result<(), Box<dyn Error>> {
+        let source = InMemory::new().with(PuzzleType::RopeBridge, "R 4\nU 2\n");
+        let lines = source
+            .open(PuzzleType::RopeBridge)?
+            .lines()
+            .collect::<Result<Vec<String>, io::Error>>()?;
+
+        assert_eq!(vec!["R 4", "U 2"], lines);
+        Ok(())
+    }
+
+    #[test]
+    fn cache_directory_should_key_by_profile_year_and_day() {
+        let source = CacheDirectory::new("cache", "alice");
+        let expected = Path::new("cache")
+            .join("alice")
+            .join("2022")
+            .join("day09.txt");
+
+        assert_eq!(
+            expected.display().to_string(),
+            source.location(PuzzleType::RopeBridge)
+        );
+    }
+
+    #[test]
+    fn missing_file_should_name_location() {
+        let source = LocalFiles::new("does-not-exist");
+        let error = source.read(PuzzleType::CalorieCounting).unwrap_err();
+        let expected = Path::new("does-not-exist").join("calorie_counting.txt");
+
+        assert_eq!(
+            format!(
+                "no input for 'calorie_counting': looked for '{}'",
+                expected.display()
+            ),
+            error.to_string()
+        );
+    }
+}
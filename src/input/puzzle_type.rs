@@ -1,12 +1,20 @@
 use std::{
     error::Error,
     fmt::{self, Debug, Display, Formatter},
+    io::BufRead,
     str::FromStr,
 };
 
-use crate::parse::error::ParseContentsError;
+use crate::{common::random::Random, parse::error::ParseContentsError};
 
-use super::puzzle_input::PuzzleInput;
+use super::{
+    puzzle_input::PuzzleInput,
+    puzzle_part::PuzzlePart,
+    report_input::{ReportError, ReportInput},
+};
+
+/// A solver that reads its input from a reader instead of a string.
+pub type StreamingSolver = fn(Box<dyn BufRead>, PuzzlePart) -> Result<String, ParseContentsError>;
 
 pub enum ParsePuzzleTypeError {
     InvalidValue(String),
@@ -27,7 +35,7 @@ impl Debug for ParsePuzzleTypeError {
 
 impl Error for ParsePuzzleTypeError {}
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PuzzleType {
     CalorieCounting,
     RockPaperScissors,
@@ -92,6 +100,44 @@ impl FromStr for PuzzleType {
 }
 
 impl PuzzleType {
+    pub fn all() -> [PuzzleType; 25] {
+        [
+            Self::CalorieCounting,
+            Self::RockPaperScissors,
+            Self::RucksackReorganization,
+            Self::CampCleanup,
+            Self::SupplyStacks,
+            Self::TuningTrouble,
+            Self::NoSpaceLeftOnDevice,
+            Self::TreetopTreeHouse,
+            Self::RopeBridge,
+            Self::CathodeRayTube,
+            Self::MonkeyInTheMiddle,
+            Self::HillClimbingAlgorithm,
+            Self::DistressSignal,
+            Self::RegolithReservoir,
+            Self::BeaconExclusionZone,
+            Self::ProboscideaVolcanium,
+            Self::PyroclasticFlow,
+            Self::BoilingBoulders,
+            Self::NotEnoughMinerals,
+            Self::GrovePositioningSystem,
+            Self::MonkeyMath,
+            Self::MonkeyMap,
+            Self::UnstableDiffusion,
+            Self::BlizzardBasin,
+            Self::FullOfHotAir,
+        ]
+    }
+
+    /// The day of the advent calendar on which the puzzle was published.
+    pub fn day(&self) -> usize {
+        Self::all()
+            .iter()
+            .position(|puzzle_type| puzzle_type == self)
+            .map_or(0, |index| index + 1)
+    }
+
     pub fn file_name(&self) -> &str {
         match self {
             Self::CalorieCounting => "calorie_counting",
@@ -163,4 +209,127 @@ impl PuzzleType {
             Self::FullOfHotAir => crate::puzzles::full_of_hot_air::full_of_hot_air,
         }
     }
+
+    /// Returns a solver that reads the input as it goes, if the puzzle can be solved without
+    /// holding the whole input in memory.
+    pub fn streaming_solver(&self) -> Option<StreamingSolver> {
+        match self {
+            Self::CalorieCounting => {
+                Some(crate::puzzles::calorie_counting::calorie_counting_stream)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn generator(&self) -> impl FnOnce(&mut Random, usize) -> String {
+        match self {
+            Self::CalorieCounting => crate::puzzles::calorie_counting::calorie_counting_generate,
+            Self::RockPaperScissors => {
+                crate::puzzles::rock_paper_scissors::rock_paper_scissors_generate
+            }
+            Self::RucksackReorganization => {
+                crate::puzzles::rucksack_reorganization::rucksack_reorganization_generate
+            }
+            Self::CampCleanup => crate::puzzles::camp_cleanup::camp_cleanup_generate,
+            Self::SupplyStacks => crate::puzzles::supply_stacks::supply_stacks_generate,
+            Self::TuningTrouble => crate::puzzles::tuning_trouble::tuning_trouble_generate,
+            Self::NoSpaceLeftOnDevice => {
+                crate::puzzles::no_space_left_on_device::no_space_left_on_device_generate
+            }
+            Self::TreetopTreeHouse => {
+                crate::puzzles::treetop_tree_house::treetop_tree_house_generate
+            }
+            Self::RopeBridge => crate::puzzles::rope_bridge::rope_bridge_generate,
+            Self::CathodeRayTube => crate::puzzles::cathode_ray_tube::cathode_ray_tube_generate,
+            Self::MonkeyInTheMiddle => {
+                crate::puzzles::monkey_in_the_middle::monkey_in_the_middle_generate
+            }
+            Self::HillClimbingAlgorithm => {
+                crate::puzzles::hill_climbing_algorithm::hill_climbing_algorithm_generate
+            }
+            Self::DistressSignal => crate::puzzles::distress_signal::distress_signal_generate,
+            Self::RegolithReservoir => {
+                crate::puzzles::regolith_reservoir::regolith_reservoir_generate
+            }
+            Self::BeaconExclusionZone => {
+                crate::puzzles::beacon_exclusion_zone::beacon_exclusion_zone_generate
+            }
+            Self::ProboscideaVolcanium => {
+                crate::puzzles::proboscidea_volcanium::proboscidea_volcanium_generate
+            }
+            Self::PyroclasticFlow => crate::puzzles::pyroclastic_flow::pyroclastic_flow_generate,
+            Self::BoilingBoulders => crate::puzzles::boiling_boulders::boiling_boulders_generate,
+            Self::NotEnoughMinerals => {
+                crate::puzzles::not_enough_minerals::not_enough_minerals_generate
+            }
+            Self::GrovePositioningSystem => {
+                crate::puzzles::grove_positioning_system::grove_positioning_system_generate
+            }
+            Self::MonkeyMath => crate::puzzles::monkey_math::monkey_math_generate,
+            Self::MonkeyMap => crate::puzzles::monkey_map::monkey_map_generate,
+            Self::UnstableDiffusion => {
+                crate::puzzles::unstable_diffusion::unstable_diffusion_generate
+            }
+            Self::BlizzardBasin => crate::puzzles::blizzard_basin::blizzard_basin_generate,
+            Self::FullOfHotAir => crate::puzzles::full_of_hot_air::full_of_hot_air_generate,
+        }
+    }
+
+    /// Returns the report function of the puzzle, if it has any report modes.
+    pub fn reporter(&self) -> Option<fn(ReportInput) -> Result<String, ReportError>> {
+        match self {
+            Self::CalorieCounting => {
+                Some(crate::puzzles::calorie_counting::calorie_counting_report)
+            }
+            Self::RockPaperScissors => {
+                Some(crate::puzzles::rock_paper_scissors::rock_paper_scissors_report)
+            }
+            Self::RucksackReorganization => {
+                Some(crate::puzzles::rucksack_reorganization::rucksack_reorganization_report)
+            }
+            Self::CampCleanup => Some(crate::puzzles::camp_cleanup::camp_cleanup_report),
+            Self::SupplyStacks => Some(crate::puzzles::supply_stacks::supply_stacks_report),
+            Self::TuningTrouble => Some(crate::puzzles::tuning_trouble::tuning_trouble_report),
+            Self::NoSpaceLeftOnDevice => {
+                Some(crate::puzzles::no_space_left_on_device::no_space_left_on_device_report)
+            }
+            Self::TreetopTreeHouse => {
+                Some(crate::puzzles::treetop_tree_house::treetop_tree_house_report)
+            }
+            Self::RopeBridge => Some(crate::puzzles::rope_bridge::rope_bridge_report),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    /// The size of the generated input and the parts to solve for a puzzle. Some solvers take too
+    /// long for larger inputs or for part 2.
+    fn generated_input_case(puzzle_type: PuzzleType) -> (usize, Vec<PuzzlePart>) {
+        match puzzle_type {
+            PuzzleType::NotEnoughMinerals => (2, vec![PuzzlePart::Part1]),
+            PuzzleType::ProboscideaVolcanium => (10, vec![PuzzlePart::Part1]),
+            _ => (50, vec![PuzzlePart::Part1, PuzzlePart::Part2]),
+        }
+    }
+
+    #[test]
+    fn generated_inputs_should_be_solvable() -> Result<(), Box<dyn Error>> {
+        for puzzle_type in PuzzleType::all() {
+            let (size, puzzle_parts) = generated_input_case(puzzle_type);
+            let file_contents = puzzle_type.generator()(&mut Random::new(1), size);
+            for puzzle_part in puzzle_parts {
+                puzzle_type.solver()(PuzzleInput {
+                    file_contents: file_contents.clone(),
+                    puzzle_part,
+                })
+                .map_err(|error| format!("{}: {}", puzzle_type.file_name(), error))?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,117 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::parse::error::ParseContentsError;
+
+/// The input of a report: an analysis of a puzzle input beyond the answers to its parts.
+pub struct ReportInput {
+    pub mode: String,
+    pub arguments: Vec<String>,
+    pub file_contents: String,
+}
+
+impl ReportInput {
+    pub fn new(mode: &str, arguments: &[&str], file_contents: &str) -> ReportInput {
+        ReportInput {
+            mode: mode.to_string(),
+            arguments: arguments
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+            file_contents: file_contents.to_string(),
+        }
+    }
+
+    /// Parses the argument at the given index, or returns `None` if there are not that many.
+    pub fn argument<T>(&self, index: usize, name: &'static str) -> Result<Option<T>, ReportError>
+    where
+        T: FromStr,
+    {
+        self.arguments
+            .get(index)
+            .map(|value| {
+                value
+                    .parse::<T>()
+                    .map_err(|_| ReportError::InvalidArgument {
+                        name,
+                        value: value.clone(),
+                    })
+            })
+            .transpose()
+    }
+
+    pub fn required_argument<T>(&self, index: usize, name: &'static str) -> Result<T, ReportError>
+    where
+        T: FromStr,
+    {
+        self.argument(index, name)?
+            .ok_or(ReportError::MissingArgument(name))
+    }
+
+    pub fn unknown_mode(&self, modes: &[&str]) -> ReportError {
+        ReportError::UnknownMode {
+            mode: self.mode.clone(),
+            modes: modes.join(", "),
+        }
+    }
+}
+
+pub enum ReportError {
+    UnknownMode {
+        mode: String,
+        modes: String,
+    },
+    MissingArgument(&'static str),
+    InvalidArgument {
+        name: &'static str,
+        value: String,
+    },
+    Contents(ParseContentsError),
+    /// The input parsed, but the report cannot be made from it with the given arguments.
+    Unsolvable(String),
+}
+
+impl Display for ReportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownMode { mode, modes } => {
+                write!(
+                    f,
+                    "unknown report mode '{}' (expected one of {})",
+                    mode, modes
+                )
+            }
+            Self::MissingArgument(name) => write!(f, "missing {}", name),
+            Self::InvalidArgument { name, value } => write!(f, "invalid {} '{}'", name, value),
+            Self::Contents(_) => write!(f, "error parsing contents"),
+            Self::Unsolvable(description) => write!(f, "{}", description),
+        }
+    }
+}
+
+impl Debug for ReportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for ReportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Contents(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<T> for ReportError
+where
+    T: Into<ParseContentsError>,
+{
+    fn from(value: T) -> Self {
+        ReportError::Contents(value.into())
+    }
+}
@@ -1,4 +1,4 @@
-use aoc2022::input::PuzzleArgs;
+use aoc2022::input::PuzzleCommand;
 use std::{convert::Infallible, env, error::Error, process};
 
 fn main() -> Result<(), Infallible> {
@@ -6,7 +6,7 @@ fn main() -> Result<(), Infallible> {
 }
 
 fn body() -> Result<(), Box<dyn Error>> {
-    PuzzleArgs::build(&mut env::args())?.run_solution()?;
+    PuzzleCommand::build(&mut env::args())?.run()?;
     Ok(())
 }
 
@@ -27,6 +27,12 @@ where
 
 impl<TError> Error for ParseByLineError<TError> where TError: Error {}
 
+impl<TError> ParseByLineError<TError> {
+    pub fn new(line: usize, error: TError) -> ParseByLineError<TError> {
+        ParseByLineError { line, error }
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "wasm", derive(Serialize))]
 pub struct ParseByLinesError<TError> {
@@ -47,6 +53,17 @@ where
 
 impl<TError> Error for ParseByLinesError<TError> where TError: Error {}
 
+impl<TError> ParseByLinesError<TError> {
+    /// Numbers the lines from the given line on, for lines parsed from a part of a larger input
+    /// whose line numbers should be reported instead.
+    pub fn numbered_from(mut self, first_line: usize) -> Self {
+        for line_error in self.line_errors.iter_mut() {
+            line_error.line += first_line;
+        }
+        self
+    }
+}
+
 impl<TError> From<ParseByLinesError<TError>> for ParseContentsError
 where
     TError: Error,
@@ -58,17 +75,17 @@ where
 
 pub struct ByLines<T>(pub Vec<T>);
 
-impl<T> FromStr for ByLines<T>
-where
-    T: FromStr,
-{
-    type Err = ParseByLinesError<T::Err>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut line_errors: Vec<ParseByLineError<T::Err>> = Vec::new();
+impl<T> ByLines<T> {
+    /// Parses every line with the given function, for values that need more than the line itself
+    /// to be parsed.
+    pub fn parse_with<TError>(
+        s: &str,
+        parse_line: impl Fn(&str) -> Result<T, TError>,
+    ) -> Result<ByLines<T>, ParseByLinesError<TError>> {
+        let mut line_errors: Vec<ParseByLineError<TError>> = Vec::new();
         let mut results: Vec<T> = Vec::new();
 
-        let line_results = s.lines().map(|line| line.parse::<T>()).enumerate();
+        let line_results = s.lines().map(parse_line).enumerate();
         for (index, line_result) in line_results {
             match line_result {
                 Ok(result) => results.push(result),
@@ -82,3 +99,14 @@ where
         }
     }
 }
+
+impl<T> FromStr for ByLines<T>
+where
+    T: FromStr,
+{
+    type Err = ParseByLinesError<T::Err>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ByLines::parse_with(s, |line| line.parse::<T>())
+    }
+}
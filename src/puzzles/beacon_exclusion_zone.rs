@@ -1,6 +1,9 @@
+#[cfg(test)]
+mod reference;
 mod sensor_reading;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
 };
@@ -37,9 +40,62 @@ fn scale() -> isize {
     2000000
 }
 
+/// Generates a sensor report with exactly one uncovered position within the search area, using
+/// four large sensors around that position and `size` smaller sensors that do not cover it.
+pub fn beacon_exclusion_zone_generate(random: &mut Random, size: usize) -> String {
+    let scale = scale() as i64;
+    let gap_x = random.range(1..(2 * scale));
+    let gap_y = random.range(0..(2 * scale + 1));
+    let mut readings: Vec<(i64, i64, i64, i64)> = Vec::new();
+
+    let offset = random.range((4 * scale)..(5 * scale));
+    for (direction_x, direction_y) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let sensor_x = gap_x + direction_x * offset;
+        let sensor_y = gap_y + direction_y * offset;
+        // One closer than the gap, so that the gap itself remains uncovered
+        let radius = 2 * offset - 1;
+        readings.push((
+            sensor_x,
+            sensor_y,
+            sensor_x + direction_x * radius,
+            sensor_y,
+        ));
+    }
+
+    for _ in 0..size {
+        let sensor_x = random.range(0..(2 * scale + 1));
+        let sensor_y = random.range(0..(2 * scale + 1));
+        let distance = (sensor_x - gap_x).abs() + (sensor_y - gap_y).abs();
+        if distance == 0 {
+            continue;
+        }
+        let radius = random.range(0..distance);
+        let radius_x = random.range(0..(radius + 1));
+        let radius_y = radius - radius_x;
+        let beacon_x = sensor_x + *random.choose(&[-1, 1]) * radius_x;
+        let beacon_y = sensor_y + *random.choose(&[-1, 1]) * radius_y;
+        readings.push((sensor_x, sensor_y, beacon_x, beacon_y));
+    }
+
+    random.shuffle(&mut readings);
+    readings
+        .into_iter()
+        .map(|(sensor_x, sensor_y, beacon_x, beacon_y)| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                sensor_x, sensor_y, beacon_x, beacon_y
+            )
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::{
+        position::Position,
+        property::{self, PuzzleInputs},
+    };
     use std::error::Error;
 
     const INPUT_TEXT: &str = "\
@@ -80,4 +136,48 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3
         assert_eq!("56000011", output);
         Ok(())
     }
+
+    #[test]
+    fn scan_should_match_reference() {
+        let generator = PuzzleInputs {
+            generate: beacon_exclusion_zone_generate,
+            sizes: 0..10,
+        };
+        property::check_against_reference(
+            &generator,
+            |file_contents| {
+                beacon_exclusion_zone(PuzzleInput {
+                    file_contents: file_contents.clone(),
+                    puzzle_part: PuzzlePart::Part2,
+                })
+                .ok()
+            },
+            |file_contents| {
+                let ByLines(readings) = file_contents.parse::<ByLines<SensorReading>>().ok()?;
+                Some(reference::scan(&readings, scale()).to_string())
+            },
+        );
+    }
+
+    #[test]
+    fn generated_input_should_leave_a_single_gap() -> Result<(), Box<dyn Error>> {
+        for seed in 1..=5 {
+            let file_contents = beacon_exclusion_zone_generate(&mut Random::new(seed), 20);
+            let ByLines(readings) = file_contents.parse::<ByLines<SensorReading>>()?;
+            let area = 0..=(2 * scale());
+            let gap_count = area
+                .clone()
+                .flat_map(|y| area.clone().map(move |x| Position { x, y }))
+                .filter(|position| {
+                    readings.iter().all(|reading| {
+                        reading.sensor.manhattan_distance(*position)
+                            > reading.sensor.manhattan_distance(reading.beacon)
+                    })
+                })
+                .count();
+
+            assert_eq!(1, gap_count);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,18 @@
+use super::sensor_reading::SensorReading;
+use crate::common::position::Position;
+
+/// Checks every position in the search area, row by row, for being out of reach of all sensors.
+pub fn scan(readings: &[SensorReading], scale: isize) -> isize {
+    for y in 0..=(scale * 2) {
+        for x in 0..=(scale * 2) {
+            let position = Position { x, y };
+            if readings.iter().all(|reading| {
+                reading.sensor.manhattan_distance(position)
+                    > reading.sensor.manhattan_distance(reading.beacon)
+            }) {
+                return x * 4000000 + y;
+            }
+        }
+    }
+    -1
+}
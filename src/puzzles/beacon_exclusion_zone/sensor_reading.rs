@@ -1,13 +1,16 @@
 use std::{
+    cmp,
     collections::HashSet,
     error::Error,
     fmt::{self, Debug, Display, Formatter},
+    ops::Range,
     str::FromStr,
 };
 
 use crate::common::{
     interval::{Interval, IntervalUnion},
     position::Position,
+    threads,
 };
 
 pub enum ParseSensorReadingError {
@@ -129,23 +132,41 @@ pub fn get_covered_position_count(readings: Vec<SensorReading>, y: isize) -> isi
 }
 
 pub fn scan(readings: Vec<SensorReading>, scale: isize) -> isize {
-    let interval = match Interval::build(0, scale * 2) {
+    let interval = match Interval::build(0, scale * 2 + 1) {
         Ok(interval) => interval,
         Err(_) => {
             return -1;
         }
     };
-    for y in 0..=(scale * 2) {
-        let slice = get_slice(&readings, y).overlap(interval);
+
+    // Every thread scans its own block of rows; the first block with a gap has the answer
+    let block_count = threads::thread_count() as isize;
+    let block_size = (scale * 2 + block_count) / block_count;
+    let blocks = (0..block_count)
+        .map(|block| (block * block_size)..cmp::min((block + 1) * block_size, scale * 2 + 1))
+        .collect::<Vec<Range<isize>>>();
+    threads::parallel_map(blocks, |rows| scan_rows(&readings, interval, rows))
+        .into_iter()
+        .find_map(|answer| answer)
+        .map_or(-1, |answer| answer)
+}
+
+fn scan_rows(
+    readings: &Vec<SensorReading>,
+    interval: Interval<isize>,
+    rows: Range<isize>,
+) -> Option<isize> {
+    for y in rows {
+        let slice = get_slice(readings, y).overlap(interval);
         if slice.0.len() == 2 {
             for p in slice.0.iter() {
                 if p.start() == 0 {
-                    return p.end() * 4000000 + y;
+                    return Some(p.end() * 4000000 + y);
                 }
             }
         }
     }
-    return -1;
+    None
 }
 
 fn get_slice(readings: &Vec<SensorReading>, y: isize) -> IntervalUnion<isize> {
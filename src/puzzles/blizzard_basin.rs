@@ -2,6 +2,7 @@ mod basin_state;
 mod basin_tile;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::error::ParseContentsError,
     puzzles::blizzard_basin::basin_state::BasinState,
@@ -25,6 +26,36 @@ pub fn blizzard_basin(input: PuzzleInput) -> Result<String, ParseContentsError>
     Ok(answer.to_string())
 }
 
+/// Generates a valley with an inner width of `size` (at least 2), and half that height. There are
+/// no vertical blizzards in the columns of the entrance and the exit, so that the blizzards never
+/// leave the valley.
+pub fn blizzard_basin_generate(random: &mut Random, size: usize) -> String {
+    let width = size.max(2);
+    let height = (size / 2).max(2);
+    let mut output = String::from("#.");
+    output.push_str(&"#".repeat(width));
+    output.push('\n');
+    for _ in 0..height {
+        output.push('#');
+        for x in 0..width {
+            let blizzards: &[char] = if x == 0 || x == width - 1 {
+                &['<', '>']
+            } else {
+                &['<', '>', '^', 'v']
+            };
+            if random.chance(1, 4) {
+                output.push(*random.choose(blizzards));
+            } else {
+                output.push('.');
+            }
+        }
+        output.push_str("#\n");
+    }
+    output.push_str(&"#".repeat(width));
+    output.push_str(".#\n");
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
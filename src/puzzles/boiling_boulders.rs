@@ -1,12 +1,15 @@
 mod boulder_collection;
+#[cfg(test)]
+mod reference;
 
 use crate::{
-    common::three_d::position3::Position3,
+    common::{random::Random, three_d::position3::Position3},
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
 };
 
 use self::boulder_collection::BoulderCollection;
+use std::collections::HashSet;
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
@@ -28,9 +31,43 @@ pub fn boiling_boulders(input: PuzzleInput) -> Result<String, ParseContentsError
     Ok(answer.to_string())
 }
 
+/// Generates a lava droplet of `size` cubes, grown from a single cube by repeatedly attaching a
+/// cube to a random side of an existing cube.
+pub fn boiling_boulders_generate(random: &mut Random, size: usize) -> String {
+    let size = size.clamp(1, 20 * 20 * 20);
+    let mut cubes: Vec<(i64, i64, i64)> = vec![(10, 10, 10)];
+    let mut occupied: HashSet<(i64, i64, i64)> = cubes.iter().copied().collect();
+    let directions = [
+        (1, 0, 0),
+        (-1, 0, 0),
+        (0, 1, 0),
+        (0, -1, 0),
+        (0, 0, 1),
+        (0, 0, -1),
+    ];
+    while cubes.len() < size {
+        let (x, y, z) = *random.choose(&cubes);
+        let (dx, dy, dz) = *random.choose(&directions);
+        let cube = (x + dx, y + dy, z + dz);
+        let in_bounds = [cube.0, cube.1, cube.2]
+            .into_iter()
+            .all(|coordinate| (0..20).contains(&coordinate));
+        if in_bounds && occupied.insert(cube) {
+            cubes.push(cube);
+        }
+    }
+
+    random.shuffle(&mut cubes);
+    cubes
+        .into_iter()
+        .map(|(x, y, z)| format!("{},{},{}\n", x, y, z))
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::property::{self, PuzzleInputs};
     use std::error::Error;
 
     const INPUT_TEXT: &str = "\
@@ -70,4 +107,26 @@ mod tests {
         assert_eq!("58", output);
         Ok(())
     }
+
+    #[test]
+    fn external_face_count_should_match_reference() {
+        let generator = PuzzleInputs {
+            generate: boiling_boulders_generate,
+            sizes: 1..30,
+        };
+        property::check_against_reference(
+            &generator,
+            |file_contents| {
+                boiling_boulders(PuzzleInput {
+                    file_contents: file_contents.clone(),
+                    puzzle_part: PuzzlePart::Part2,
+                })
+                .ok()
+            },
+            |file_contents| {
+                let ByLines(positions) = file_contents.parse::<ByLines<Position3<isize>>>().ok()?;
+                Some(reference::external_face_count(&positions).to_string())
+            },
+        );
+    }
 }
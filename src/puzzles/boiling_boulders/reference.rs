@@ -0,0 +1,50 @@
+use crate::common::three_d::position3::Position3;
+use std::collections::{HashSet, VecDeque};
+
+/// Counts the faces that touch the steam, by flooding the bounding box around the droplet (with a
+/// margin of 1) from one of its corners.
+pub fn external_face_count(positions: &[Position3<isize>]) -> usize {
+    let cubes = positions
+        .iter()
+        .map(|position| (position.x, position.y, position.z))
+        .collect::<HashSet<(isize, isize, isize)>>();
+    if cubes.is_empty() {
+        return 0;
+    }
+
+    let min = |coordinate: fn(&(isize, isize, isize)) -> isize| {
+        cubes.iter().map(coordinate).min().map_or(0, |x| x) - 1
+    };
+    let max = |coordinate: fn(&(isize, isize, isize)) -> isize| {
+        cubes.iter().map(coordinate).max().map_or(0, |x| x) + 1
+    };
+    let (min_x, min_y, min_z) = (min(|c| c.0), min(|c| c.1), min(|c| c.2));
+    let (max_x, max_y, max_z) = (max(|c| c.0), max(|c| c.1), max(|c| c.2));
+
+    let mut steam: HashSet<(isize, isize, isize)> = HashSet::from([(min_x, min_y, min_z)]);
+    let mut queue: VecDeque<(isize, isize, isize)> = VecDeque::from([(min_x, min_y, min_z)]);
+    let mut face_count = 0;
+    while let Some((x, y, z)) = queue.pop_front() {
+        let neighbours = [
+            (x + 1, y, z),
+            (x - 1, y, z),
+            (x, y + 1, z),
+            (x, y - 1, z),
+            (x, y, z + 1),
+            (x, y, z - 1),
+        ];
+        for neighbour in neighbours {
+            let (nx, ny, nz) = neighbour;
+            if nx < min_x || nx > max_x || ny < min_y || ny > max_y || nz < min_z || nz > max_z {
+                continue;
+            }
+            if cubes.contains(&neighbour) {
+                face_count += 1;
+            } else if steam.insert(neighbour) {
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    face_count
+}
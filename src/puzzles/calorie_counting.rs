@@ -1,7 +1,27 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter, Write},
+    io::{self, BufRead},
+    num::ParseIntError,
+};
+
 use crate::{
-    common::collection::max_items::AsMaxItems,
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
-    parse::{error::ParseContentsError, lines::ByLines, sections::BySections},
+    common::{
+        collection::max_items::{AsMaxItems, TopItems},
+        random::Random,
+    },
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
+    parse::{
+        error::ParseContentsError,
+        lines::ByLines,
+        sections::{AsSections, BySections},
+    },
 };
 
 #[cfg(feature = "wasm")]
@@ -23,20 +43,255 @@ pub fn calorie_counting_validate(input: JsValue) -> JsValue {
 
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn calorie_counting(input: PuzzleInput) -> Result<String, ParseContentsError> {
-    let BySections(calorie_counts) = input.file_contents.parse::<BySections<ByLines<u64>>>()?;
-    let count = match input.puzzle_part {
+    calorie_counting_stream(input.file_contents.as_bytes(), input.puzzle_part)
+}
+
+/// Solves the puzzle while reading the input, so that the input never has to fit in memory.
+pub fn calorie_counting_stream(
+    reader: impl BufRead,
+    puzzle_part: PuzzlePart,
+) -> Result<String, ParseContentsError> {
+    let count = match puzzle_part {
         PuzzlePart::Part1 => 1,
         PuzzlePart::Part2 => 3,
     };
 
-    let answer = calorie_counts
+    let answer = top_calories(reader, count)?;
+
+    Ok(answer.to_string())
+}
+
+/// An error while summing calories. Sections and lines are both numbered from 1.
+pub enum SumCaloriesError {
+    Read { line: usize, error: io::Error },
+    InvalidItem { line: usize, error: ParseIntError },
+    Overflow { section: usize, first_line: usize },
+    TopOverflow(usize),
+}
+
+impl Display for SumCaloriesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { line, error } => write!(f, "error reading line {}: {}", line, error),
+            Self::InvalidItem { line, error } => {
+                write!(f, "invalid item at line {}: {}", line, error)
+            }
+            Self::Overflow {
+                section,
+                first_line,
+            } => write!(
+                f,
+                "calories in section {} (starting at line {}) overflow u64",
+                section, first_line
+            ),
+            Self::TopOverflow(count) => {
+                write!(f, "calories of the top {} elves overflow u64", count)
+            }
+        }
+    }
+}
+
+impl Debug for SumCaloriesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for SumCaloriesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Read { line: _, error } => Some(error),
+            Self::InvalidItem { line: _, error } => Some(error),
+            Self::Overflow { .. } | Self::TopOverflow(_) => None,
+        }
+    }
+}
+
+impl From<SumCaloriesError> for ParseContentsError {
+    fn from(value: SumCaloriesError) -> Self {
+        ParseContentsError::new(value)
+    }
+}
+
+struct SectionSum {
+    section: usize,
+    first_line: usize,
+    total: u64,
+}
+
+/// Sums the calories of each elf while reading, keeping only the largest `count` totals in memory,
+/// and returns the sum of those totals.
+pub fn top_calories(reader: impl BufRead, count: usize) -> Result<u64, SumCaloriesError> {
+    // Totals are kept apart by section, so that elves carrying the same amount all count
+    let mut top_totals: TopItems<(u64, Reverse<usize>)> = TopItems::new(count);
+    let mut current: Option<SectionSum> = None;
+    let mut section_count: usize = 0;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.map_err(|error| SumCaloriesError::Read {
+            line: line_number,
+            error,
+        })?;
+
+        if line.trim().is_empty() {
+            if let Some(sum) = current.take() {
+                top_totals.push((sum.total, Reverse(sum.section)));
+            }
+            continue;
+        }
+
+        let item = line
+            .parse::<u64>()
+            .map_err(|error| SumCaloriesError::InvalidItem {
+                line: line_number,
+                error,
+            })?;
+        let sum = current.get_or_insert_with(|| {
+            section_count += 1;
+            SectionSum {
+                section: section_count,
+                first_line: line_number,
+                total: 0,
+            }
+        });
+        sum.total = sum
+            .total
+            .checked_add(item)
+            .ok_or(SumCaloriesError::Overflow {
+                section: sum.section,
+                first_line: sum.first_line,
+            })?;
+    }
+    if let Some(sum) = current {
+        top_totals.push((sum.total, Reverse(sum.section)));
+    }
+
+    top_totals
+        .into_vec()
         .into_iter()
-        .map(|ByLines(group)| group.into_iter().sum::<u64>())
+        .try_fold(0u64, |total, (section_total, _)| {
+            total.checked_add(section_total)
+        })
+        .ok_or(SumCaloriesError::TopOverflow(count))
+}
+
+struct ElfInventory {
+    /// The index of the section, numbered from 0 unlike in the report.
+    section: usize,
+    first_line: usize,
+    item_count: usize,
+    total: u64,
+}
+
+fn elf_inventories(file_contents: &str) -> Result<Vec<ElfInventory>, ParseContentsError> {
+    let BySections(calorie_counts) = file_contents.parse::<BySections<ByLines<u64>>>()?;
+
+    Ok(file_contents
+        .sections()
+        .zip(calorie_counts)
+        .enumerate()
+        .map(
+            |(section, (content_section, ByLines(items)))| ElfInventory {
+                section,
+                first_line: content_section.starts_at_line + 1,
+                item_count: items.len(),
+                total: items.into_iter().sum(),
+            },
+        )
+        .collect())
+}
+
+/// Reports on the elves in the input. The `ranking` mode lists the elves carrying the most calories
+/// (all of them, unless a count is given), with shared ranks for ties.
+pub fn calorie_counting_report(input: ReportInput) -> Result<String, ReportError> {
+    match input.mode.as_str() {
+        "ranking" => {
+            let inventories = elf_inventories(&input.file_contents)?;
+            let count = input
+                .argument::<usize>(0, "count")?
+                .unwrap_or(inventories.len());
+            Ok(ranking(&inventories, count))
+        }
+        _ => Err(input.unknown_mode(&["ranking"])),
+    }
+}
+
+fn ranking(inventories: &[ElfInventory], count: usize) -> String {
+    let mut total_counts: HashMap<u64, usize> = HashMap::new();
+    for inventory in inventories.iter() {
+        *total_counts.entry(inventory.total).or_insert(0) += 1;
+    }
+    // Ties are ordered by section, and have to be kept apart to all be listed
+    let ranked = inventories
+        .iter()
+        .map(|inventory| (inventory.total, Reverse(inventory.section)))
         .max_items(count)
         .into_iter()
-        .sum::<u64>();
+        .map(|(_, Reverse(section))| &inventories[section])
+        .collect::<Vec<&ElfInventory>>();
 
-    Ok(answer.to_string())
+    let mut output = String::new();
+    writeln!(output, "rank  section   line  items     total").unwrap();
+    let mut rank: usize = 0;
+    for (index, inventory) in ranked.iter().enumerate() {
+        if index == 0 || ranked[index - 1].total != inventory.total {
+            rank = index + 1;
+        }
+        let tie_marker = if total_counts[&inventory.total] > 1 {
+            "="
+        } else {
+            ""
+        };
+        writeln!(
+            output,
+            "{:>4}{:1} {:>7} {:>6} {:>6} {:>9}",
+            rank,
+            tie_marker,
+            inventory.section + 1,
+            inventory.first_line,
+            inventory.item_count,
+            inventory.total
+        )
+        .unwrap();
+    }
+    writeln!(
+        output,
+        "total of top {}: {}",
+        ranked.len(),
+        ranked.iter().map(|inventory| inventory.total).sum::<u64>()
+    )
+    .unwrap();
+
+    if let Some(last) = ranked.last() {
+        let listed_count = ranked
+            .iter()
+            .filter(|inventory| inventory.total == last.total)
+            .count();
+        let unlisted_count = total_counts[&last.total] - listed_count;
+        if unlisted_count > 0 {
+            writeln!(
+                output,
+                "tie at the cutoff: {} more elves carry {} calories",
+                unlisted_count, last.total
+            )
+            .unwrap();
+        }
+    }
+
+    output
+}
+
+/// Generates `size` elf inventories of one to six food items each.
+pub fn calorie_counting_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..random.range(1..7))
+                .map(|_| format!("{}\n", random.range(1000..70000)))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 #[cfg(test)]
@@ -82,4 +337,84 @@ mod tests {
         assert_eq!("45000", output);
         Ok(())
     }
+
+    #[test]
+    fn top_calories_should_count_ties_separately() -> Result<(), Box<dyn Error>> {
+        let total = top_calories("300\n\n100\n200\n\n50\n".as_bytes(), 2)?;
+
+        assert_eq!(600, total);
+        Ok(())
+    }
+
+    #[test]
+    fn top_calories_should_detect_overflow() {
+        let input_text = "1\n\n18446744073709551615\n1\n";
+        let error = top_calories(input_text.as_bytes(), 1).unwrap_err();
+
+        assert_eq!(
+            "calories in section 2 (starting at line 3) overflow u64",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn top_calories_should_detect_overflow_of_top_sum() {
+        let input_text = "18446744073709551615\n\n1\n";
+        let error = top_calories(input_text.as_bytes(), 2).unwrap_err();
+
+        assert_eq!(
+            "calories of the top 2 elves overflow u64",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn ranking_should_list_every_elf() -> Result<(), Box<dyn Error>> {
+        let output = calorie_counting_report(ReportInput::new("ranking", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+rank  section   line  items     total
+   1        4     10      3     24000
+   2        3      7      2     11000
+   3        5     14      1     10000
+   4        1      1      3      6000
+   5        2      5      1      4000
+total of top 5: 55000
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ranking_should_report_ties() -> Result<(), Box<dyn Error>> {
+        let input_text = "100\n200\n\n300\n\n50\n\n300\n\n150\n150\n";
+        let output = calorie_counting_report(ReportInput::new("ranking", &["2"], input_text))?;
+
+        assert_eq!(
+            "\
+rank  section   line  items     total
+   1=       1      1      2       300
+   1=       2      4      1       300
+total of top 2: 600
+tie at the cutoff: 2 more elves carry 300 calories
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn generated_input_should_have_one_to_six_items_per_elf() {
+        let file_contents = calorie_counting_generate(&mut Random::new(1), 50);
+        let item_counts = file_contents
+            .as_str()
+            .sections()
+            .map(|section| section.contents.lines().count())
+            .collect::<Vec<usize>>();
+
+        assert_eq!(50, item_counts.len());
+        assert!(item_counts.iter().all(|count| (1..=6).contains(count)));
+    }
 }
@@ -1,8 +1,19 @@
+mod coverage;
 mod lib;
 
-use self::lib::CampAssignment;
+use std::fmt::Write;
+
+use self::{
+    coverage::Coverage,
+    lib::{CampAssignment, CampSection},
+};
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
     parse::{error::ParseContentsError, lines::ByLines},
 };
 
@@ -15,14 +26,134 @@ pub fn camp_cleanup(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let answer = camp_assignments
         .iter()
         .filter(|camp_assignment| match input.puzzle_part {
-            PuzzlePart::Part1 => camp_assignment.one_is_contained_in_other(),
-            PuzzlePart::Part2 => camp_assignment.overlaps(),
+            PuzzlePart::Part1 => camp_assignment.one_contains_all_others(),
+            PuzzlePart::Part2 => camp_assignment.all_overlap(),
         })
         .count();
 
     Ok(answer.to_string())
 }
 
+/// Reports on all assignments together. The modes are:
+/// - `assignments`: the number of lines on which some range contains all others, on which all
+///   ranges overlap, and the same for some pair of ranges on a line.
+/// - `coverage`: the sections that are assigned to nobody, to exactly one elf or to several, and
+///   the sections that are assigned most often.
+/// - `pairs`: the pairs of elves on different lines whose sections overlap.
+pub fn camp_cleanup_report(input: ReportInput) -> Result<String, ReportError> {
+    let ByLines(camp_assignments) = input.file_contents.parse::<ByLines<CampAssignment>>()?;
+    match input.mode.as_str() {
+        "assignments" => Ok(assignments_report(&camp_assignments)),
+        "coverage" => Ok(coverage_report(&camp_assignments)),
+        "pairs" => Ok(pairs_report(&camp_assignments)),
+        _ => Err(input.unknown_mode(&["assignments", "coverage", "pairs"])),
+    }
+}
+
+/// A property of the ranges on a line, with its description in the `assignments` report.
+type AssignmentPredicate = (&'static str, fn(&CampAssignment) -> bool);
+
+fn assignments_report(camp_assignments: &[CampAssignment]) -> String {
+    let predicates: [AssignmentPredicate; 4] = [
+        (
+            "some range contains all others",
+            CampAssignment::one_contains_all_others,
+        ),
+        ("all ranges overlap", CampAssignment::all_overlap),
+        (
+            "some range contains another",
+            CampAssignment::some_pair_is_contained,
+        ),
+        ("some ranges overlap", CampAssignment::some_pair_overlaps),
+    ];
+
+    let mut output = String::new();
+    for (description, predicate) in predicates {
+        let count = camp_assignments
+            .iter()
+            .filter(|camp_assignment| predicate(camp_assignment))
+            .count();
+        writeln!(output, "lines on which {}: {}", description, count).unwrap();
+    }
+    output
+}
+
+fn coverage_report(camp_assignments: &[CampAssignment]) -> String {
+    let sections = camp_assignments
+        .iter()
+        .flat_map(|camp_assignment| camp_assignment.sections().iter().copied())
+        .collect::<Vec<CampSection>>();
+    let coverage = Coverage::sweep(&sections);
+
+    let mut output = String::new();
+    for (description, union) in [
+        ("nobody", &coverage.nobody),
+        ("exactly one elf", &coverage.exactly_one),
+        ("several elves", &coverage.several),
+    ] {
+        writeln!(
+            output,
+            "covered by {}: {} section{} ({})",
+            description,
+            union.count(),
+            if union.count() == 1 { "" } else { "s" },
+            coverage::section_ranges(union)
+        )
+        .unwrap();
+    }
+    writeln!(
+        output,
+        "maximum coverage depth: {} ({})",
+        coverage.maximum_depth,
+        coverage::section_ranges(&coverage.deepest)
+    )
+    .unwrap();
+    output
+}
+
+fn pairs_report(camp_assignments: &[CampAssignment]) -> String {
+    let pairs = coverage::overlapping_pairs(camp_assignments);
+    let describe = |elf: coverage::Elf| {
+        let section = camp_assignments[elf.line].sections()[elf.index];
+        format!(
+            "line {} elf {} ({}-{})",
+            elf.line + 1,
+            elf.index + 1,
+            section.start,
+            section.end
+        )
+    };
+
+    let mut output = String::new();
+    for (first, second) in pairs.iter() {
+        writeln!(output, "{} and {}", describe(*first), describe(*second)).unwrap();
+    }
+    writeln!(
+        output,
+        "{} overlapping pair{} across lines",
+        pairs.len(),
+        if pairs.len() == 1 { "" } else { "s" }
+    )
+    .unwrap();
+    output
+}
+
+/// Generates `size` pairs of section assignments.
+pub fn camp_cleanup_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let first_start = random.range(1..100);
+            let first_end = random.range(first_start..100);
+            let second_start = random.range(1..100);
+            let second_end = random.range(second_start..100);
+            format!(
+                "{}-{},{}-{}\n",
+                first_start, first_end, second_start, second_end
+            )
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +189,80 @@ mod tests {
         assert_eq!("4", output);
         Ok(())
     }
+
+    const N_WAY_INPUT_TEXT: &str = "\
+2-8,3-7,4-4
+2-4,6-8,3-5
+1-5,4-6,5-9
+5-7
+";
+
+    #[test]
+    fn example_1_with_n_way_assignments() -> Result<(), Box<dyn Error>> {
+        let output = camp_cleanup(PuzzleInput {
+            file_contents: N_WAY_INPUT_TEXT.to_string(),
+            puzzle_part: PuzzlePart::Part1,
+        })?;
+
+        assert_eq!("2", output);
+        Ok(())
+    }
+
+    #[test]
+    fn example_2_with_n_way_assignments() -> Result<(), Box<dyn Error>> {
+        let output = camp_cleanup(PuzzleInput {
+            file_contents: N_WAY_INPUT_TEXT.to_string(),
+            puzzle_part: PuzzlePart::Part2,
+        })?;
+
+        assert_eq!("3", output);
+        Ok(())
+    }
+
+    #[test]
+    fn assignments_should_count_pairwise_variants() -> Result<(), Box<dyn Error>> {
+        let output = camp_cleanup_report(ReportInput::new("assignments", &[], N_WAY_INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+lines on which some range contains all others: 2
+lines on which all ranges overlap: 3
+lines on which some range contains another: 1
+lines on which some ranges overlap: 3
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_should_count_sections_by_depth() -> Result<(), Box<dyn Error>> {
+        let output = camp_cleanup_report(ReportInput::new("coverage", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+covered by nobody: 0 sections (none)
+covered by exactly one elf: 1 section (9)
+covered by several elves: 7 sections (2-8)
+maximum coverage depth: 8 (6)
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pairs_should_list_overlaps_across_lines() -> Result<(), Box<dyn Error>> {
+        let input_text = "1-2,5-6\n2-3,7-9\n";
+        let output = camp_cleanup_report(ReportInput::new("pairs", &[], input_text))?;
+
+        assert_eq!(
+            "\
+line 1 elf 1 (1-2) and line 2 elf 1 (2-3)
+1 overlapping pair across lines
+",
+            output
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,122 @@
+use std::{cmp, collections::BTreeMap};
+
+use crate::common::interval::{Interval, IntervalUnion};
+
+use super::lib::{CampAssignment, CampSection};
+
+/// How often the sections between the lowest and the highest assigned section are assigned.
+pub struct Coverage {
+    pub nobody: IntervalUnion<usize>,
+    pub exactly_one: IntervalUnion<usize>,
+    pub several: IntervalUnion<usize>,
+    pub maximum_depth: usize,
+    pub deepest: IntervalUnion<usize>,
+}
+
+impl Coverage {
+    /// Sweeps over the starts and ends of the sections, keeping track of how many elves are assigned
+    /// to the sections in between.
+    pub fn sweep(sections: &[CampSection]) -> Coverage {
+        // Sections are inclusive, so an elf stops covering sections right after the end
+        let mut depth_changes: BTreeMap<usize, isize> = BTreeMap::new();
+        for section in sections.iter() {
+            *depth_changes.entry(section.start).or_insert(0) += 1;
+            *depth_changes.entry(section.end + 1).or_insert(0) -= 1;
+        }
+
+        let mut coverage = Coverage {
+            nobody: IntervalUnion::new(),
+            exactly_one: IntervalUnion::new(),
+            several: IntervalUnion::new(),
+            maximum_depth: 0,
+            deepest: IntervalUnion::new(),
+        };
+        let mut depth: isize = 0;
+        let mut changes = depth_changes.into_iter().peekable();
+        while let Some((position, change)) = changes.next() {
+            depth += change;
+            let next_position = match changes.peek() {
+                Some((next_position, _)) => *next_position,
+                None => break,
+            };
+            let interval = Interval::build(position, next_position).unwrap();
+            let depth = depth as usize;
+            match depth {
+                0 => coverage.nobody.add(&interval),
+                1 => coverage.exactly_one.add(&interval),
+                _ => coverage.several.add(&interval),
+            }
+            match depth.cmp(&coverage.maximum_depth) {
+                cmp::Ordering::Greater => {
+                    coverage.maximum_depth = depth;
+                    coverage.deepest = IntervalUnion::new();
+                    coverage.deepest.add(&interval);
+                }
+                cmp::Ordering::Equal if depth > 0 => coverage.deepest.add(&interval),
+                _ => {}
+            }
+        }
+
+        coverage
+    }
+}
+
+/// An elf, as the line of its assignment and its position on the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Elf {
+    pub line: usize,
+    pub index: usize,
+}
+
+/// Finds the pairs of elves on different lines whose sections overlap, ordered by the first elf and
+/// then by the second.
+pub fn overlapping_pairs(assignments: &[CampAssignment]) -> Vec<(Elf, Elf)> {
+    let mut elves = assignments
+        .iter()
+        .enumerate()
+        .flat_map(|(line, assignment)| {
+            assignment
+                .sections()
+                .iter()
+                .enumerate()
+                .map(move |(index, section)| (Elf { line, index }, *section))
+        })
+        .collect::<Vec<(Elf, CampSection)>>();
+    elves.sort_by_key(|(_, section)| section.start);
+
+    // Every elf that is still active when another one starts overlaps with it
+    let mut pairs: Vec<(Elf, Elf)> = Vec::new();
+    let mut active: Vec<(Elf, CampSection)> = Vec::new();
+    for (elf, section) in elves {
+        active.retain(|(_, active_section)| active_section.end >= section.start);
+        for (active_elf, _) in active.iter() {
+            if active_elf.line != elf.line {
+                pairs.push((cmp::min(*active_elf, elf), cmp::max(*active_elf, elf)));
+            }
+        }
+        active.push((elf, section));
+    }
+
+    pairs.sort();
+    pairs
+}
+
+/// Lists the sections of a union as inclusive ranges.
+pub fn section_ranges(union: &IntervalUnion<usize>) -> String {
+    let mut intervals = union.0.iter().collect::<Vec<&Interval<usize>>>();
+    if intervals.is_empty() {
+        return String::from("none");
+    }
+    intervals.sort_by_key(|interval| interval.start());
+    intervals
+        .into_iter()
+        .map(|interval| {
+            if interval.count() == 1 {
+                interval.start().to_string()
+            } else {
+                format!("{}-{}", interval.start(), interval.end() - 1)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
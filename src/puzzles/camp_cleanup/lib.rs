@@ -5,17 +5,14 @@ use std::{
 };
 
 pub enum ParseCampAssignmentError {
-    InvalidNumberOfRanges(usize),
     InvalidNumberOfBounds(usize, usize),
     ParseIntError(usize, usize),
+    EndBeforeStart(usize),
 }
 
 impl Display for ParseCampAssignmentError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::InvalidNumberOfRanges(count) => {
-                write!(f, "contains {} ranges, instead of the expected 2", count)
-            }
             Self::InvalidNumberOfBounds(index, count) => write!(
                 f,
                 "range {} contains {} bounds, instead of the expected 2",
@@ -28,6 +25,9 @@ impl Display for ParseCampAssignmentError {
                 bound_index + 1,
                 range_index + 1
             ),
+            Self::EndBeforeStart(index) => {
+                write!(f, "range {} ends before it starts", index + 1)
+            }
         }
     }
 }
@@ -46,21 +46,25 @@ pub struct CampSection {
     pub end: usize, // Note: end is inclusive
 }
 
-pub struct CampAssignment([CampSection; 2]);
+impl CampSection {
+    pub fn contains(&self, other: &CampSection) -> bool {
+        self.start <= other.start && self.end >= other.end
+    }
+
+    pub fn overlaps(&self, other: &CampSection) -> bool {
+        self.start <= other.end && self.end >= other.start
+    }
+}
+
+/// The sections assigned to the elves of a group, one range per elf.
+pub struct CampAssignment(Vec<CampSection>);
 
 impl FromStr for CampAssignment {
     type Err = ParseCampAssignmentError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let sections = s.split(',').collect::<Vec<&str>>();
-        if sections.len() != 2 {
-            return Err(ParseCampAssignmentError::InvalidNumberOfRanges(
-                sections.len(),
-            ));
-        }
-
-        let sections = sections
-            .iter()
+        let sections = s
+            .split(',')
             .enumerate()
             .map(|(section_index, section)| {
                 let bounds = section.split('-').collect::<Vec<&str>>();
@@ -78,6 +82,9 @@ impl FromStr for CampAssignment {
                             .map_err(|_| ParseCampAssignmentError::ParseIntError(section_index, i))
                     })
                     .collect::<Result<Vec<usize>, ParseCampAssignmentError>>()?;
+                if bounds[1] < bounds[0] {
+                    return Err(ParseCampAssignmentError::EndBeforeStart(section_index));
+                }
                 Ok(CampSection {
                     start: bounds[0],
                     end: bounds[1],
@@ -85,19 +92,48 @@ impl FromStr for CampAssignment {
             })
             .collect::<Result<Vec<CampSection>, ParseCampAssignmentError>>()?;
 
-        Ok(CampAssignment([sections[0], sections[1]]))
+        Ok(CampAssignment(sections))
     }
 }
 
 impl CampAssignment {
-    pub fn one_is_contained_in_other(&self) -> bool {
+    pub fn sections(&self) -> &[CampSection] {
+        &self.0
+    }
+
+    /// Whether some range contains all other ranges. A line with a single range always has one.
+    pub fn one_contains_all_others(&self) -> bool {
+        let CampAssignment(sections) = self;
+        sections
+            .iter()
+            .any(|section| sections.iter().all(|other| section.contains(other)))
+    }
+
+    /// Whether all ranges share at least one section. A single range always does, by itself.
+    pub fn all_overlap(&self) -> bool {
         let CampAssignment(sections) = self;
-        (sections[0].start >= sections[1].start && sections[0].end <= sections[1].end)
-            || (sections[0].start <= sections[1].start && sections[0].end >= sections[1].end)
+        let latest_start = sections.iter().map(|section| section.start).max();
+        let earliest_end = sections.iter().map(|section| section.end).min();
+        latest_start <= earliest_end
+    }
+
+    /// Whether some range contains another range.
+    pub fn some_pair_is_contained(&self) -> bool {
+        self.some_pair(CampSection::contains)
+    }
+
+    /// Whether some two ranges share at least one section.
+    pub fn some_pair_overlaps(&self) -> bool {
+        self.some_pair(CampSection::overlaps)
     }
 
-    pub fn overlaps(&self) -> bool {
+    fn some_pair(&self, predicate: impl Fn(&CampSection, &CampSection) -> bool) -> bool {
         let CampAssignment(sections) = self;
-        sections[0].start <= sections[1].end && sections[0].end >= sections[1].start
+        sections.iter().enumerate().any(|(index, section)| {
+            sections
+                .iter()
+                .enumerate()
+                .any(|(other_index, other)| index != other_index && predicate(section, other))
+        })
     }
 }
@@ -2,7 +2,7 @@ mod signal_change;
 
 use self::signal_change::SignalChange;
 use crate::{
-    common::vector_chunks::AsVectorChunks,
+    common::{random::Random, vector_chunks::AsVectorChunks},
     input::puzzle_input::PuzzleInput,
     input::puzzle_part::PuzzlePart,
     parse::{error::ParseContentsError, lines::ByLines},
@@ -62,6 +62,22 @@ pub fn cathode_ray_tube(input: PuzzleInput) -> Result<String, ParseContentsError
     Ok(answer.to_string())
 }
 
+/// Generates `size` instructions, keeping the X register within the width of the screen.
+pub fn cathode_ray_tube_generate(random: &mut Random, size: usize) -> String {
+    let mut x: i64 = 1;
+    (0..size)
+        .map(|_| {
+            if random.chance(1, 3) {
+                String::from("noop\n")
+            } else {
+                let value = random.range((-x).max(-15)..(40 - x).min(16));
+                x += value;
+                format!("addx {}\n", value)
+            }
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,6 +1,7 @@
 mod packet;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines, sections::BySections},
 };
@@ -52,6 +53,33 @@ pub fn distress_signal(input: PuzzleInput) -> Result<String, ParseContentsError>
     Ok(answer.to_string())
 }
 
+/// Generates `size` pairs of randomly nested packets.
+pub fn distress_signal_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{}\n{}\n",
+                generate_packet(random, 4),
+                generate_packet(random, 4)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn generate_packet(random: &mut Random, depth: usize) -> String {
+    let items = (0..random.range(0..5))
+        .map(|_| {
+            if depth > 0 && random.chance(1, 3) {
+                generate_packet(random, depth - 1)
+            } else {
+                random.range(0..11).to_string()
+            }
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", items.join(","))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
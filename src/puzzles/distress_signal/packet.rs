@@ -121,3 +121,77 @@ impl Ord for Packet {
         self.cmp(other)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{
+        property::{self, Generator, Integers},
+        random::Random,
+    };
+
+    /// Generates packets nested up to the given depth, shrinking towards their contents.
+    struct Packets(usize);
+
+    impl Packets {
+        fn generate_with_depth(random: &mut Random, depth: usize) -> Packet {
+            if depth == 0 || random.chance(1, 3) {
+                Packet::Constant(random.below(11))
+            } else {
+                Packet::List(
+                    (0..random.below(4))
+                        .map(|_| Self::generate_with_depth(random, depth - 1))
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    impl Generator for Packets {
+        type Item = Packet;
+
+        fn generate(&self, random: &mut Random) -> Packet {
+            Self::generate_with_depth(random, self.0)
+        }
+
+        fn shrink(&self, item: &Packet) -> Vec<Packet> {
+            match item {
+                Packet::Constant(value) => Integers(0..11)
+                    .shrink(&(*value as i64))
+                    .into_iter()
+                    .map(|value| Packet::Constant(value as usize))
+                    .collect(),
+                Packet::List(items) => {
+                    let mut candidates = items.clone();
+                    for index in 0..items.len() {
+                        let mut removed = items.clone();
+                        removed.remove(index);
+                        candidates.push(Packet::List(removed));
+                    }
+                    for (index, packet) in items.iter().enumerate() {
+                        for shrunk in self.shrink(packet) {
+                            let mut replaced = items.clone();
+                            replaced[index] = shrunk;
+                            candidates.push(Packet::List(replaced));
+                        }
+                    }
+                    candidates
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ordering_should_be_antisymmetric() {
+        property::check(&(Packets(3), Packets(3)), |(a, b)| {
+            a.cmp(b) == b.cmp(a).reverse()
+        });
+    }
+
+    #[test]
+    fn ordering_should_be_transitive() {
+        property::check(&(Packets(3), Packets(3), Packets(3)), |(a, b, c)| {
+            !(a <= b && b <= c) || a <= c
+        });
+    }
+}
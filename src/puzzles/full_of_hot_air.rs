@@ -1,6 +1,7 @@
 mod snafu;
 
 use crate::{
+    common::random::Random,
     input::puzzle_input::PuzzleInput,
     parse::{error::ParseContentsError, lines::ByLines},
     puzzles::full_of_hot_air::snafu::Snafu,
@@ -16,6 +17,20 @@ pub fn full_of_hot_air(input: PuzzleInput) -> Result<String, ParseContentsError>
     Ok(answer.to_string())
 }
 
+/// Generates `size` SNAFU numbers of up to 20 digits.
+pub fn full_of_hot_air_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let mut line = random.choose(&['1', '2']).to_string();
+            for _ in 0..random.range(0..20) {
+                line.push(*random.choose(&['=', '-', '0', '1', '2']));
+            }
+            line.push('\n');
+            line
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::input::puzzle_part::PuzzlePart;
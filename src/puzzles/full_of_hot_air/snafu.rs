@@ -104,3 +104,33 @@ impl Sum for Snafu {
         snafu
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::property::{self, Integers, Strings};
+
+    #[test]
+    fn display_should_round_trip_through_parse() {
+        property::check(&Integers(1..1000000000000), |value| {
+            match Snafu(*value).to_string().parse::<Snafu>() {
+                Ok(Snafu(parsed)) => parsed == *value,
+                Err(_) => false,
+            }
+        });
+    }
+
+    #[test]
+    fn parse_should_round_trip_through_display() {
+        property::check(
+            &(Strings::new("12", 1..2), Strings::new("=-012", 0..20)),
+            |(first, rest)| {
+                let s = format!("{}{}", first, rest);
+                match s.parse::<Snafu>() {
+                    Ok(snafu) => snafu.to_string() == s,
+                    Err(_) => false,
+                }
+            },
+        );
+    }
+}
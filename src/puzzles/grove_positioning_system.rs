@@ -1,6 +1,7 @@
 mod mill;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
     puzzles::grove_positioning_system::mill::Mill,
@@ -46,6 +47,26 @@ pub fn grove_positioning_system(input: PuzzleInput) -> Result<String, ParseConte
     Ok(answer.to_string())
 }
 
+/// Generates `size` numbers (at least 1), exactly one of which is 0.
+pub fn grove_positioning_system_generate(random: &mut Random, size: usize) -> String {
+    let mut numbers = (1..size.max(1))
+        .map(|_| {
+            let number = random.range(-10000..10000);
+            if number == 0 {
+                1
+            } else {
+                number
+            }
+        })
+        .collect::<Vec<i64>>();
+    numbers.push(0);
+    random.shuffle(&mut numbers);
+    numbers
+        .into_iter()
+        .map(|number| format!("{}\n", number))
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +103,16 @@ mod tests {
         assert_eq!("1623178306", output);
         Ok(())
     }
+
+    #[test]
+    fn generated_input_should_have_a_single_zero() -> Result<(), Box<dyn Error>> {
+        for size in [1, 2, 50] {
+            let file_contents = grove_positioning_system_generate(&mut Random::new(1), size);
+            let ByLines(numbers) = file_contents.parse::<ByLines<i64>>()?;
+
+            assert_eq!(size, numbers.len());
+            assert_eq!(1, numbers.iter().filter(|number| **number == 0).count());
+        }
+        Ok(())
+    }
 }
@@ -216,6 +216,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::property::{self, Integers, Vectors};
 
     #[test]
     fn should_create() {
@@ -337,4 +338,31 @@ mod tests {
         assert_eq!(mill.get_value_previous_from(0, 2), 42);
         assert_eq!(mill.get_value_previous_from(0, 3), 13);
     }
+
+    #[test]
+    fn mixing_should_preserve_values() {
+        let generator = Vectors {
+            element: Integers(-50..50),
+            lengths: 2..20,
+        };
+        property::check(&generator, |values| {
+            let mill: Mill<i64> = values.clone().into();
+            for index in 0..mill.len() {
+                let value = mill.get_value(index);
+                if value < 0 {
+                    mill.move_value_previous(index, value.abs_diff(0));
+                } else if value > 0 {
+                    mill.move_value_next(index, value.abs_diff(0));
+                }
+            }
+
+            let mut mixed = (0..mill.len())
+                .map(|count| mill.get_value_next_from(0, count))
+                .collect::<Vec<i64>>();
+            let mut values = values.clone();
+            mixed.sort();
+            values.sort();
+            mixed == values
+        });
+    }
 }
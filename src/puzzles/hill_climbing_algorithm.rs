@@ -1,6 +1,7 @@
 mod elevation_grid;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::error::ParseContentsError,
 };
@@ -29,6 +30,32 @@ pub fn hill_climbing_algorithm(input: PuzzleInput) -> Result<String, ParseConten
     }
 }
 
+/// Generates a heightmap of `size` columns, which rises from the start in the top left corner to
+/// the best signal in the bottom right corner.
+pub fn hill_climbing_algorithm_generate(random: &mut Random, size: usize) -> String {
+    let width = size.max(3);
+    let height = (size / 3).max(2);
+    let distance = (width + height - 2) as i64;
+    (0..height)
+        .map(|y| {
+            let mut line = (0..width)
+                .map(|x| {
+                    if x == 0 && y == 0 {
+                        'S'
+                    } else if x == width - 1 && y == height - 1 {
+                        'E'
+                    } else {
+                        let elevation = 25 * (x + y) as i64 / distance + random.range(-2..3);
+                        char::from(b'a' + elevation.clamp(0, 25) as u8)
+                    }
+                })
+                .collect::<String>();
+            line.push('\n');
+            line
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -8,6 +8,7 @@ mod starting_items;
 
 use self::monkey::MonkeyCollection;
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::error::ParseContentsError,
 };
@@ -31,6 +32,48 @@ pub fn monkey_in_the_middle(input: PuzzleInput) -> Result<String, ParseContentsE
     Ok(answer.to_string())
 }
 
+/// Generates between 2 and 9 monkeys, depending on `size`. The monkeys only throw items in a way
+/// that keeps worry levels small enough for part 1.
+pub fn monkey_in_the_middle_generate(random: &mut Random, size: usize) -> String {
+    let monkey_count = size.clamp(2, 9);
+    let mut divisors: Vec<u64> = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+    random.shuffle(&mut divisors);
+    // The last monkey squares worry levels, so no other monkey throws items to it
+    let has_squarer = monkey_count >= 3;
+    let receiving_count = if has_squarer {
+        monkey_count - 1
+    } else {
+        monkey_count
+    };
+
+    (0..monkey_count)
+        .map(|index| {
+            let starting_items = (0..random.range(1..5))
+                .map(|_| random.range(50..100).to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            let operation = if has_squarer && index == monkey_count - 1 {
+                String::from("old * old")
+            } else {
+                format!("old {} {}", random.choose(&["+", "*"]), random.range(2..7))
+            };
+            // Monkeys only throw to monkeys that come earlier, except for the first monkey
+            let (start, end) = if index == 0 {
+                (1, receiving_count)
+            } else {
+                (0, index.min(receiving_count))
+            };
+            let if_true = start + random.below(end - start);
+            let if_false = start + random.below(end - start);
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                index, starting_items, operation, divisors[index], if_true, if_false
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
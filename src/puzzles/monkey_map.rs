@@ -7,7 +7,7 @@ mod travel_instruction;
 use std::collections::HashMap;
 
 use crate::{
-    common::direction::Direction,
+    common::{direction::Direction, random::Random},
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, section_pair::SectionPair},
     puzzles::monkey_map::{
@@ -326,6 +326,62 @@ fn create_cube_glueing() -> Glueing {
     Glueing { map, resolution: 4 }
 }
 
+/// Generates a map with the same net as the glueings used by the solver, with about one in ten
+/// tiles being a wall, followed by a path of `size` moves (at least 1).
+pub fn monkey_map_generate(random: &mut Random, size: usize) -> String {
+    let resolution = create_cube_glueing().resolution;
+    let faces = net_faces();
+    let width = faces.iter().map(|(x, _)| x + 1).max().map_or(0, |x| x) * resolution;
+    let height = faces.iter().map(|(_, y)| y + 1).max().map_or(0, |y| y) * resolution;
+
+    let mut map = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    if !faces.contains(&(x / resolution, y / resolution)) {
+                        ' '
+                    } else if random.chance(1, 10) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    // The starting position needs to be open
+    if let Some(start) = map[0].iter_mut().find(|tile| **tile != ' ') {
+        *start = '.';
+    }
+
+    let mut output = map
+        .into_iter()
+        .map(|row| {
+            let mut line = row.into_iter().collect::<String>().trim_end().to_string();
+            line.push('\n');
+            line
+        })
+        .collect::<String>();
+    output.push('\n');
+    output.push_str(&random.range(1..(2 * resolution as i64)).to_string());
+    for _ in 1..size {
+        output.push(*random.choose(&['L', 'R']));
+        output.push_str(&random.range(1..(2 * resolution as i64)).to_string());
+    }
+    output.push('\n');
+    output
+}
+
+#[cfg(not(test))]
+fn net_faces() -> [(usize, usize); 6] {
+    [(1, 0), (2, 0), (1, 1), (0, 2), (1, 2), (0, 3)]
+}
+
+#[cfg(test)]
+fn net_faces() -> [(usize, usize); 6] {
+    [(2, 0), (0, 1), (1, 1), (2, 1), (2, 2), (3, 2)]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,12 +4,14 @@ mod monkey_job;
 mod symphony;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
     puzzles::monkey_math::{cacophony::Cacophony, monkey_job::MonkeyJob},
 };
 
 use self::symphony::Symphony;
+use std::collections::HashSet;
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
@@ -38,6 +40,88 @@ pub fn monkey_math(input: PuzzleInput) -> Result<String, ParseContentsError> {
     Ok(answer.to_string())
 }
 
+/// Generates a tree of monkeys with about `size` yelling monkeys. All divisions are exact, and the
+/// number that `humn` yells is also the number needed to pass the equality test of `root`.
+pub fn monkey_math_generate(random: &mut Random, size: usize) -> String {
+    let mut names: HashSet<String> = HashSet::from([String::from("root"), String::from("humn")]);
+    let mut lines: Vec<String> = Vec::new();
+    let size = size.max(2) as i64;
+    let left_size = random.range(1..size);
+    let value = random.range(1..1000);
+    let lhs = generate_monkey(random, &mut names, &mut lines, value, left_size, true);
+    let rhs = generate_monkey(
+        random,
+        &mut names,
+        &mut lines,
+        value,
+        size - left_size,
+        false,
+    );
+    lines.push(format!("root: {} + {}\n", lhs, rhs));
+    random.shuffle(&mut lines);
+    lines.into_iter().collect::<String>()
+}
+
+fn generate_monkey(
+    random: &mut Random,
+    names: &mut HashSet<String>,
+    lines: &mut Vec<String>,
+    value: i64,
+    size: i64,
+    has_human: bool,
+) -> String {
+    let name = if size <= 1 && has_human {
+        String::from("humn")
+    } else {
+        loop {
+            let name = (0..4)
+                .map(|_| char::from(b'a' + random.below(26) as u8))
+                .collect::<String>();
+            if names.insert(name.clone()) {
+                break name;
+            }
+        }
+    };
+    if size <= 1 {
+        lines.push(format!("{}: {}\n", name, value));
+        return name;
+    }
+
+    let left_size = random.range(1..size);
+    let right_size = size - left_size;
+    // The human is always on the left, unless it is explicitly swapped
+    let (lhs, op, rhs, can_swap) = match random.below(4) {
+        0 => {
+            let lhs = random.range(-1000..1000);
+            (lhs, "+", value - lhs, true)
+        }
+        1 => {
+            let rhs = random.range(-1000..1000);
+            (value + rhs, "-", rhs, true)
+        }
+        2 if value.abs() < 1000000 => (value * 3, "/", 3, false),
+        _ => {
+            let divisor = (2..10)
+                .rev()
+                .find(|divisor| value % divisor == 0)
+                .unwrap_or(1);
+            (value / divisor, "*", divisor, value / divisor != 0)
+        }
+    };
+    let human_on_right = has_human && can_swap && random.chance(1, 2);
+    let lhs = generate_monkey(
+        random,
+        names,
+        lines,
+        lhs,
+        left_size,
+        has_human && !human_on_right,
+    );
+    let rhs = generate_monkey(random, names, lines, rhs, right_size, human_on_right);
+    lines.push(format!("{}: {} {} {}\n", name, lhs, op, rhs));
+    name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +166,22 @@ hmdt: 32
         assert_eq!("301", output);
         Ok(())
     }
+
+    #[test]
+    fn generated_input_should_be_solved_by_the_human_number() -> Result<(), Box<dyn Error>> {
+        for seed in 1..=5 {
+            let file_contents = monkey_math_generate(&mut Random::new(seed), 50);
+            let human_number = file_contents
+                .lines()
+                .find_map(|line| line.strip_prefix("humn: "))
+                .ok_or("no human")?;
+            let output = monkey_math(PuzzleInput {
+                file_contents: file_contents.clone(),
+                puzzle_part: PuzzlePart::Part2,
+            })?;
+
+            assert_eq!(human_number, output);
+        }
+        Ok(())
+    }
 }
@@ -1,9 +1,27 @@
 mod command_line;
+mod disk;
 mod file_tree;
 
-use self::{command_line::CommandLine, file_tree::Directory};
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter, Write},
+    str::FromStr,
+};
+
+use self::{
+    command_line::CommandLine,
+    disk::{DiskSpace, UpdateSpace},
+    file_tree::{Directory, LogWarning},
+};
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
     parse::{error::ParseContentsError, lines::ByLines},
 };
 
@@ -12,25 +30,306 @@ use wasm_bindgen::prelude::*;
 
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn no_space_left_on_device(input: PuzzleInput) -> Result<String, ParseContentsError> {
-    let ByLines(command_lines) = input.file_contents.parse::<ByLines<CommandLine>>()?;
-    let file_system = command_lines.into_iter().collect::<Directory>();
+    let file_system = parse_file_system(&input.file_contents)?;
     let answer = match input.puzzle_part {
-        PuzzlePart::Part1 => {
-            let sizes = file_system.get_directories_smaller_than(100000);
-            sizes.iter().map(|(_, size)| size).sum::<usize>()
-        }
+        PuzzlePart::Part1 => file_system
+            .directories()
+            .into_iter()
+            .map(|directory| directory.size)
+            .filter(|size| *size <= 100000)
+            .sum::<usize>(),
         PuzzlePart::Part2 => {
-            let main_directory_size = file_system.get_size();
-            let target_size = main_directory_size - 40000000;
-            let mut size = file_system.get_directories_larger_than(target_size);
-            size.sort_by(|(_, x), (_, y)| x.cmp(y));
-            let (_, size) = size[0];
-            size
+            match DiskSpace::default()
+                .update_space(&file_system)
+                .map_err(ParseContentsError::new)?
+            {
+                UpdateSpace::Enough { .. } => return Ok(String::from("no deletion needed")),
+                UpdateSpace::Delete { directory } => directory.size,
+            }
         }
     };
     Ok(answer.to_string())
 }
 
+fn parse_file_system(file_contents: &str) -> Result<Directory, ParseContentsError> {
+    let ByLines(command_lines) = file_contents.parse::<ByLines<CommandLine>>()?;
+    Ok(command_lines.into_iter().collect::<Directory>())
+}
+
+pub enum ParseFindCriterionError {
+    InvalidFormat(String),
+    InvalidSize(String),
+}
+
+impl Display for ParseFindCriterionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat(input) => write!(
+                f,
+                "criterion '{}' is not one of name=<name>, min=<size> or max=<size>",
+                input
+            ),
+            Self::InvalidSize(input) => write!(f, "invalid size '{}'", input),
+        }
+    }
+}
+
+impl Debug for ParseFindCriterionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for ParseFindCriterionError {}
+
+/// What the `find` report looks for: items with a given name, or of at least or at most a size.
+enum FindCriterion {
+    Name(String),
+    MinimumSize(usize),
+    MaximumSize(usize),
+}
+
+impl FromStr for FindCriterion {
+    type Err = ParseFindCriterionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_size = |size: &str| {
+            size.parse::<usize>()
+                .map_err(|_| Self::Err::InvalidSize(size.to_string()))
+        };
+        match s.split_once('=') {
+            Some(("name", name)) => Ok(Self::Name(name.to_string())),
+            Some(("min", size)) => Ok(Self::MinimumSize(parse_size(size)?)),
+            Some(("max", size)) => Ok(Self::MaximumSize(parse_size(size)?)),
+            _ => Err(Self::Err::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+impl FindCriterion {
+    fn matches(&self, name: &str, size: usize) -> bool {
+        match self {
+            Self::Name(expected) => name == expected,
+            Self::MinimumSize(minimum) => size >= *minimum,
+            Self::MaximumSize(maximum) => size <= *maximum,
+        }
+    }
+}
+
+/// Reports on the terminal log, or on the reconstructed file system below the directory at the
+/// path given as first argument. The modes are:
+/// - `check`: the lines of the log that do not add up, such as changing into a directory that was
+///   never listed.
+/// - `tree [path]`: the directory drawn as a tree, as in the puzzle statement.
+/// - `du <path>`: the total size of every directory.
+/// - `find <path> <criterion>`: the directories and files matching `name=<name>`, `min=<size>` or
+///   `max=<size>`.
+/// - `largest <path> <count>`: the given number of largest files.
+/// - `free <path> <size>`: the smallest directory whose deletion frees at least the given size.
+/// - `update [capacity] [required]`: the directory to delete to make room for the update, on a
+///   disk of the given capacity (70000000 by default) with the update requiring the given free
+///   space (30000000 by default).
+pub fn no_space_left_on_device_report(input: ReportInput) -> Result<String, ReportError> {
+    let mut output = String::new();
+    match input.mode.as_str() {
+        "check" => {
+            let (_, warnings) = parse_log(&input.file_contents)?;
+            for warning in warnings.iter() {
+                writeln!(output, "{}", warning).unwrap();
+            }
+            writeln!(output, "{} warnings", warnings.len()).unwrap();
+        }
+        "tree" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let path = input
+                .argument::<String>(0, "path")?
+                .unwrap_or_else(|| String::from("/"));
+            write!(output, "{}", directory_at(&file_system, path)?).unwrap();
+        }
+        "du" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let path = input.required_argument::<String>(0, "path")?;
+            for directory in directory_at(&file_system, path)?.directories() {
+                writeln!(output, "{:>10}  {}", directory.size, directory.path).unwrap();
+            }
+        }
+        "find" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let path = input.required_argument::<String>(0, "path")?;
+            let criterion = input.required_argument::<FindCriterion>(1, "criterion")?;
+            for directory in directory_at(&file_system, path)?.directories() {
+                if criterion.matches(directory.name(), directory.size) {
+                    writeln!(output, "{} (dir, size={})", directory.path, directory.size).unwrap();
+                }
+                for file in directory.files.iter() {
+                    if criterion.matches(&file.name, file.size) {
+                        let path = directory.child_path(&file.name);
+                        writeln!(output, "{} (file, size={})", path, file.size).unwrap();
+                    }
+                }
+            }
+        }
+        "largest" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let path = input.required_argument::<String>(0, "path")?;
+            let count = input.required_argument::<usize>(1, "count")?;
+            let mut files = directory_at(&file_system, path)?.files();
+            files.sort_by_key(|(path, file)| (Reverse(file.size), path.clone()));
+            for (path, file) in files.into_iter().take(count) {
+                writeln!(output, "{:>10}  {}", file.size, path).unwrap();
+            }
+        }
+        "free" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let path = input.required_argument::<String>(0, "path")?;
+            let size = input.required_argument::<usize>(1, "size")?;
+            match directory_at(&file_system, path)?.smallest_directory_of_at_least(size) {
+                Some(directory) => {
+                    writeln!(output, "{:>10}  {}", directory.size, directory.path).unwrap()
+                }
+                None => writeln!(output, "no directory holds {} or more", size).unwrap(),
+            }
+        }
+        "update" => {
+            let (file_system, _) = parse_log(&input.file_contents)?;
+            let default = DiskSpace::default();
+            let disk_space = DiskSpace {
+                capacity: input
+                    .argument::<usize>(0, "capacity")?
+                    .unwrap_or(default.capacity),
+                required: input
+                    .argument::<usize>(1, "required")?
+                    .unwrap_or(default.required),
+            };
+            match disk_space
+                .update_space(&file_system)
+                .map_err(|error| ReportError::Unsolvable(error.to_string()))?
+            {
+                UpdateSpace::Enough { free } => {
+                    writeln!(output, "no deletion needed: {} is free already", free).unwrap()
+                }
+                UpdateSpace::Delete { directory } => writeln!(
+                    output,
+                    "delete {} to free {}",
+                    directory.path, directory.size
+                )
+                .unwrap(),
+            }
+        }
+        _ => {
+            return Err(
+                input.unknown_mode(&["check", "tree", "du", "find", "largest", "free", "update"])
+            )
+        }
+    }
+    Ok(output)
+}
+
+fn parse_log(file_contents: &str) -> Result<(Directory, Vec<LogWarning>), ParseContentsError> {
+    let ByLines(command_lines) = file_contents.parse::<ByLines<CommandLine>>()?;
+    Ok(Directory::from_log(command_lines))
+}
+
+fn directory_at(file_system: &Directory, path: String) -> Result<&Directory, ReportError> {
+    file_system
+        .get_directory(&path)
+        .ok_or(ReportError::InvalidArgument {
+            name: "path",
+            value: path,
+        })
+}
+
+/// Generates a terminal log that explores a file system with `size` directories besides the root.
+/// The total size always exceeds the 40000000 that part 2 expects to be in use.
+pub fn no_space_left_on_device_generate(random: &mut Random, size: usize) -> String {
+    let mut subdirectories: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut directory_names: Vec<String> = vec![String::from("/")];
+    let mut items: Vec<Vec<String>> = vec![Vec::new()];
+    let mut names: Vec<HashSet<String>> = vec![HashSet::new()];
+    let mut total_size: i64 = 0;
+
+    for index in 1..=size {
+        let parent = random.below(index);
+        let name = generate_unique_name(random, &mut names[parent]);
+        items[parent].push(format!("dir {}", name));
+        subdirectories[parent].push(index);
+        subdirectories.push(Vec::new());
+        directory_names.push(name);
+        items.push(Vec::new());
+        names.push(HashSet::new());
+    }
+
+    for directory in 0..=size {
+        for _ in 0..random.range(0..5) {
+            let file_size = random.range(1000..300000);
+            total_size += file_size;
+            let mut name = generate_unique_name(random, &mut names[directory]);
+            if random.chance(1, 2) {
+                name.push('.');
+                let extension = random.choose(&["txt", "dat", "log", "lst", "ext"]);
+                name.push_str(extension);
+            }
+            items[directory].push(format!("{} {}", file_size, name));
+        }
+    }
+    if total_size < 50000000 {
+        let name = generate_unique_name(random, &mut names[0]);
+        items[0].push(format!("{} {}", 50000000 - total_size, name));
+    }
+
+    let mut output = String::from("$ cd /\n");
+    write_listing(
+        random,
+        &mut subdirectories,
+        &directory_names,
+        &mut items,
+        0,
+        &mut output,
+    );
+    output
+}
+
+fn generate_unique_name(random: &mut Random, names: &mut HashSet<String>) -> String {
+    loop {
+        let name = (0..random.range(1..8))
+            .map(|_| char::from(b'a' + random.below(26) as u8))
+            .collect::<String>();
+        if names.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+fn write_listing(
+    random: &mut Random,
+    subdirectories: &mut [Vec<usize>],
+    directory_names: &[String],
+    items: &mut [Vec<String>],
+    directory: usize,
+    output: &mut String,
+) {
+    output.push_str("$ ls\n");
+    random.shuffle(&mut items[directory]);
+    for item in items[directory].iter() {
+        output.push_str(item);
+        output.push('\n');
+    }
+
+    random.shuffle(&mut subdirectories[directory]);
+    for subdirectory in subdirectories[directory].clone() {
+        output.push_str(&format!("$ cd {}\n", directory_names[subdirectory]));
+        write_listing(
+            random,
+            subdirectories,
+            directory_names,
+            items,
+            subdirectory,
+            output,
+        );
+        output.push_str("$ cd ..\n");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +382,219 @@ $ ls
         assert_eq!("24933642", output);
         Ok(())
     }
+
+    #[test]
+    fn tree_should_draw_directories_like_the_puzzle() -> Result<(), Box<dyn Error>> {
+        let output = no_space_left_on_device_report(ReportInput::new("tree", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn check_should_warn_about_inconsistent_log() -> Result<(), Box<dyn Error>> {
+        let file_contents = "\
+$ cd /
+$ cd ..
+$ ls
+dir a
+14848514 b.txt
+$ cd b
+$ cd ..
+$ cd a
+12 c
+$ ls
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+$ ls
+14848514 b.txt
+";
+        let output = no_space_left_on_device_report(ReportInput::new("check", &[], file_contents))?;
+
+        assert_eq!(
+            "\
+line 2: changes out of the root directory
+line 6: changes into directory 'b', which was never listed
+line 9: item shown outside of a listing
+line 15: lists / with other contents than before
+4 warnings
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn listing_again_should_not_count_files_twice() -> Result<(), Box<dyn Error>> {
+        let file_contents = format!("{}$ cd /\n$ ls\ndir a\n14848514 b.txt\n", INPUT_TEXT);
+        let output = no_space_left_on_device(PuzzleInput {
+            file_contents,
+            puzzle_part: PuzzlePart::Part1,
+        })?;
+
+        assert_eq!("95437", output);
+        Ok(())
+    }
+
+    #[test]
+    fn du_should_list_directory_sizes() -> Result<(), Box<dyn Error>> {
+        let output = no_space_left_on_device_report(ReportInput::new("du", &["/"], INPUT_TEXT))?;
+
+        assert_eq!(
+            "  48381165  /\n     94853  /a\n       584  /a/e\n  24933642  /d\n",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn find_should_match_names_and_sizes() -> Result<(), Box<dyn Error>> {
+        let by_name =
+            no_space_left_on_device_report(ReportInput::new("find", &["/", "name=e"], INPUT_TEXT))?;
+        let by_size = no_space_left_on_device_report(ReportInput::new(
+            "find",
+            &["/a", "max=3000"],
+            INPUT_TEXT,
+        ))?;
+
+        assert_eq!("/a/e (dir, size=584)\n", by_name);
+        assert_eq!(
+            "/a/g (file, size=2557)\n/a/e (dir, size=584)\n/a/e/i (file, size=584)\n",
+            by_size
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn largest_should_list_largest_files() -> Result<(), Box<dyn Error>> {
+        let output =
+            no_space_left_on_device_report(ReportInput::new("largest", &["/", "3"], INPUT_TEXT))?;
+
+        assert_eq!(
+            "  14848514  /b.txt\n   8504156  /c.dat\n   8033020  /d/d.log\n",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn free_should_find_smallest_directory_of_at_least_size() -> Result<(), Box<dyn Error>> {
+        let found =
+            no_space_left_on_device_report(ReportInput::new("free", &["/", "90000"], INPUT_TEXT))?;
+        let missing = no_space_left_on_device_report(ReportInput::new(
+            "free",
+            &["/a", "100000"],
+            INPUT_TEXT,
+        ))?;
+
+        assert_eq!("     94853  /a\n", found);
+        assert_eq!("no directory holds 100000 or more\n", missing);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_mode_should_be_rejected_before_arguments() {
+        let error =
+            no_space_left_on_device_report(ReportInput::new("bogus", &[], INPUT_TEXT)).unwrap_err();
+
+        assert_eq!(
+            "unknown report mode 'bogus' (expected one of check, tree, du, find, largest, free, update)",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn update_should_depend_on_disk_space() -> Result<(), Box<dyn Error>> {
+        let default = no_space_left_on_device_report(ReportInput::new("update", &[], INPUT_TEXT))?;
+        let larger_update = no_space_left_on_device_report(ReportInput::new(
+            "update",
+            &["70000000", "50000000"],
+            INPUT_TEXT,
+        ))?;
+        let larger_disk =
+            no_space_left_on_device_report(ReportInput::new("update", &["80000000"], INPUT_TEXT))?;
+
+        assert_eq!("delete /d to free 24933642\n", default);
+        assert_eq!("delete / to free 48381165\n", larger_update);
+        assert_eq!(
+            "no deletion needed: 31618835 is free already\n",
+            larger_disk
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn update_should_fail_if_it_cannot_fit() {
+        let too_large = no_space_left_on_device_report(ReportInput::new(
+            "update",
+            &["70000000", "80000000"],
+            INPUT_TEXT,
+        ));
+        let overfull =
+            no_space_left_on_device_report(ReportInput::new("update", &["40000000"], INPUT_TEXT));
+
+        assert_eq!(
+            "the update needs 80000000 free, more than the disk capacity of 70000000",
+            too_large.unwrap_err().to_string()
+        );
+        assert_eq!(
+            "the file system uses 48381165, more than the disk capacity of 40000000",
+            overfull.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn part_2_should_fail_on_overfull_disk() {
+        let output = no_space_left_on_device(PuzzleInput {
+            file_contents: String::from("$ cd /\n$ ls\n80000000 a\n"),
+            puzzle_part: PuzzlePart::Part2,
+        });
+
+        assert_eq!(
+            "the file system uses 80000000, more than the disk capacity of 70000000\n",
+            output.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn part_2_should_not_delete_on_roomy_disk() -> Result<(), Box<dyn Error>> {
+        let output = no_space_left_on_device(PuzzleInput {
+            file_contents: "$ cd /\n$ ls\n100 a\n".to_string(),
+            puzzle_part: PuzzlePart::Part2,
+        })?;
+
+        assert_eq!("no deletion needed", output);
+        Ok(())
+    }
+
+    #[test]
+    fn generated_input_should_exceed_space_in_use_for_part_2() -> Result<(), Box<dyn Error>> {
+        for size in [0, 1, 50] {
+            let file_contents = no_space_left_on_device_generate(&mut Random::new(1), size);
+            let file_system = parse_file_system(&file_contents)?;
+
+            assert!(file_system.size > 40000000);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,85 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use super::file_tree::Directory;
+
+pub enum UpdateSpaceError {
+    DiskOverfull { used: usize, capacity: usize },
+    UpdateTooLarge { required: usize, capacity: usize },
+}
+
+impl Display for UpdateSpaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DiskOverfull { used, capacity } => write!(
+                f,
+                "the file system uses {}, more than the disk capacity of {}",
+                used, capacity
+            ),
+            Self::UpdateTooLarge { required, capacity } => write!(
+                f,
+                "the update needs {} free, more than the disk capacity of {}",
+                required, capacity
+            ),
+        }
+    }
+}
+
+impl Debug for UpdateSpaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for UpdateSpaceError {}
+
+/// How to make room for the update: either there is enough free space already, or a directory has
+/// to be deleted.
+pub enum UpdateSpace<'a> {
+    Enough { free: usize },
+    Delete { directory: &'a Directory },
+}
+
+/// The capacity of the disk, and the free space that the update requires.
+#[derive(Clone, Copy)]
+pub struct DiskSpace {
+    pub capacity: usize,
+    pub required: usize,
+}
+
+impl Default for DiskSpace {
+    fn default() -> Self {
+        DiskSpace {
+            capacity: 70000000,
+            required: 30000000,
+        }
+    }
+}
+
+impl DiskSpace {
+    /// Finds the smallest directory to delete to make room for the update, if any.
+    pub fn update_space<'a>(
+        &self,
+        file_system: &'a Directory,
+    ) -> Result<UpdateSpace<'a>, UpdateSpaceError> {
+        let free =
+            self.capacity
+                .checked_sub(file_system.size)
+                .ok_or(UpdateSpaceError::DiskOverfull {
+                    used: file_system.size,
+                    capacity: self.capacity,
+                })?;
+        if free >= self.required {
+            return Ok(UpdateSpace::Enough { free });
+        }
+        file_system
+            .smallest_directory_of_at_least(self.required - free)
+            .map(|directory| UpdateSpace::Delete { directory })
+            .ok_or(UpdateSpaceError::UpdateTooLarge {
+                required: self.required,
+                capacity: self.capacity,
+            })
+    }
+}
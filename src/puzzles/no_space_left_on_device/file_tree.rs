@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+};
 
 use super::command_line::{ChangeDirectoryTarget, CommandLine, DirectoryItem};
 
@@ -8,28 +11,44 @@ pub struct File {
     pub name: String,
 }
 
+/// A directory of the reconstructed file system, with its absolute path and the total size of its
+/// contents, which is computed once the whole log has been read.
 #[derive(Debug, Clone)]
 pub struct Directory {
-    pub directories: HashMap<String, Directory>,
+    pub path: String,
+    pub size: usize,
+    pub directories: BTreeMap<String, Directory>,
     pub files: Vec<File>,
 }
 
 impl Directory {
-    fn new() -> Directory {
+    fn new(path: String) -> Directory {
         Directory {
-            directories: HashMap::new(),
+            path,
+            size: 0,
+            directories: BTreeMap::new(),
             files: Vec::new(),
         }
     }
 
-    pub fn get_size(&self) -> usize {
+    /// Returns the absolute path of an item in this directory.
+    pub fn child_path(&self, name: &str) -> String {
+        if self.path.ends_with('/') {
+            format!("{}{}", self.path, name)
+        } else {
+            format!("{}/{}", self.path, name)
+        }
+    }
+
+    fn update_sizes(&mut self) -> usize {
         let file_sum = self.files.iter().map(|file| file.size).sum::<usize>();
         let directory_sum = self
             .directories
-            .values()
-            .map(|dir| dir.get_size())
+            .values_mut()
+            .map(|dir| dir.update_sizes())
             .sum::<usize>();
-        file_sum + directory_sum
+        self.size = file_sum + directory_sum;
+        self.size
     }
 
     fn get_directory_mut(&mut self, path: &Vec<String>) -> Option<&mut Directory> {
@@ -41,35 +60,139 @@ impl Directory {
         Some(current)
     }
 
-    // TODO Really inefficient...
-    pub fn get_directories_smaller_than(&self, bound: usize) -> Vec<(&Directory, usize)> {
-        let mut directory_sizes = self
-            .directories
-            .values()
-            .flat_map(|dir| dir.get_directories_smaller_than(bound))
-            .collect::<Vec<(&Directory, usize)>>();
-        let directory_size = self.get_size();
-        if directory_size <= bound {
-            directory_sizes.push((self, directory_size));
+    /// Looks up a directory by its path below this directory, with or without a leading `/`. Paths
+    /// cannot go up with `..`.
+    pub fn get_directory(&self, path: &str) -> Option<&Directory> {
+        let mut current = self;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            current = current.directories.get(name)?;
+        }
+        Some(current)
+    }
+
+    /// Returns this directory and all directories below it, each before its subdirectories, and
+    /// those in order of name.
+    pub fn directories(&self) -> Vec<&Directory> {
+        let mut directories = Vec::new();
+        self.collect_directories(&mut directories);
+        directories
+    }
+
+    fn collect_directories<'a>(&'a self, directories: &mut Vec<&'a Directory>) {
+        directories.push(self);
+        for directory in self.directories.values() {
+            directory.collect_directories(directories);
+        }
+    }
+
+    /// Returns the smallest of this directory and the directories below it that holds at least the
+    /// given size.
+    pub fn smallest_directory_of_at_least(&self, size: usize) -> Option<&Directory> {
+        self.directories()
+            .into_iter()
+            .filter(|directory| directory.size >= size)
+            .min_by_key(|directory| directory.size)
+    }
+
+    /// Returns the absolute paths and the files in this directory and all directories below it.
+    pub fn files(&self) -> Vec<(String, &File)> {
+        self.directories()
+            .into_iter()
+            .flat_map(|directory| {
+                directory
+                    .files
+                    .iter()
+                    .map(|file| (directory.child_path(&file.name), file))
+            })
+            .collect()
+    }
+}
+
+/// Draws the directory as an indented tree, with the items of every directory in order of name.
+impl Display for Directory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_tree(f, self.name(), 0)
+    }
+}
+
+impl Directory {
+    pub fn name(&self) -> &str {
+        match self.path.rsplit_once('/') {
+            Some((_, "")) | None => &self.path,
+            Some((_, name)) => name,
         }
-        directory_sizes
     }
 
-    // TODO Really inefficient...
-    pub fn get_directories_larger_than(&self, bound: usize) -> Vec<(&Directory, usize)> {
-        let mut directory_sizes = self
+    fn write_tree(&self, f: &mut Formatter<'_>, name: &str, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}- {} (dir)", "", name, indent = depth * 2)?;
+
+        let mut items = self
             .directories
-            .values()
-            .flat_map(|dir| dir.get_directories_larger_than(bound))
-            .collect::<Vec<(&Directory, usize)>>();
-        let directory_size = self.get_size();
-        if directory_size >= bound {
-            directory_sizes.push((self, directory_size));
+            .iter()
+            .map(|(name, directory)| (name.as_str(), Some(directory), directory.size))
+            .chain(
+                self.files
+                    .iter()
+                    .map(|file| (file.name.as_str(), None, file.size)),
+            )
+            .collect::<Vec<(&str, Option<&Directory>, usize)>>();
+        items.sort_by_key(|(name, _, _)| *name);
+        for (name, directory, size) in items {
+            match directory {
+                Some(directory) => directory.write_tree(f, name, depth + 1)?,
+                None => writeln!(
+                    f,
+                    "{:indent$}- {} (file, size={})",
+                    "",
+                    name,
+                    size,
+                    indent = (depth + 1) * 2
+                )?,
+            }
         }
-        directory_sizes
+        Ok(())
     }
 }
 
+/// Something in a terminal log that does not add up, with the line at which it shows. Lines are
+/// numbered from 1, as in all reports, rather than from 0 as in `ParseByLineError`.
+pub enum LogWarning {
+    UnlistedDirectory { line: usize, name: String },
+    OutOfRoot { line: usize },
+    ListedDifferently { line: usize, path: String },
+    ItemOutsideListing { line: usize },
+}
+
+impl Display for LogWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnlistedDirectory { line, name } => write!(
+                f,
+                "line {}: changes into directory '{}', which was never listed",
+                line, name
+            ),
+            Self::OutOfRoot { line } => {
+                write!(f, "line {}: changes out of the root directory", line)
+            }
+            Self::ListedDifferently { line, path } => write!(
+                f,
+                "line {}: lists {} with other contents than before",
+                line, path
+            ),
+            Self::ItemOutsideListing { line } => {
+                write!(f, "line {}: item shown outside of a listing", line)
+            }
+        }
+    }
+}
+
+/// A listing of a directory that is still being read, with its items as they were shown.
+struct Listing {
+    line: usize,
+    path: String,
+    items: Vec<String>,
+}
+
 pub struct CommandLineIterator<T>
 where
     T: Iterator<Item = CommandLine>,
@@ -77,28 +200,74 @@ where
     source: T,
     file_system: Directory,
     current_path: Vec<String>, // TODO I'd really like a reference to a directory, and not some string path...
+    line: usize,
+    listing: Option<Listing>,
+    listings: HashMap<String, Vec<String>>,
+    warnings: Vec<LogWarning>,
 }
 
 impl<T> CommandLineIterator<T>
 where
     T: Iterator<Item = CommandLine>,
 {
-    fn process_command(&mut self, command_line: CommandLine) -> () {
+    fn process_command(&mut self, command_line: CommandLine) {
+        self.line += 1;
         match command_line {
-            CommandLine::List => {}
-            CommandLine::ChangeDirectory(target) => self.process_directory_change(target),
+            CommandLine::List => {
+                self.finish_listing();
+                self.listing = Some(Listing {
+                    line: self.line,
+                    path: format!("/{}", self.current_path.join("/")),
+                    items: Vec::new(),
+                });
+            }
+            CommandLine::ChangeDirectory(target) => {
+                self.finish_listing();
+                self.process_directory_change(target)
+            }
             CommandLine::Item(directory_item) => self.process_item(directory_item),
         }
     }
 
-    fn process_directory_change(&mut self, target: ChangeDirectoryTarget) -> () {
+    /// Compares the listing that was being read to an earlier one of the same directory, if any.
+    fn finish_listing(&mut self) {
+        if let Some(mut listing) = self.listing.take() {
+            listing.items.sort();
+            match self.listings.get(&listing.path) {
+                Some(items) if *items != listing.items => {
+                    self.warnings.push(LogWarning::ListedDifferently {
+                        line: listing.line,
+                        path: listing.path,
+                    })
+                }
+                Some(_) => {}
+                None => {
+                    self.listings.insert(listing.path, listing.items);
+                }
+            }
+        }
+    }
+
+    fn process_directory_change(&mut self, target: ChangeDirectoryTarget) {
         match target {
             ChangeDirectoryTarget::Into(name) => {
+                let current = self.file_system.get_directory_mut(&self.current_path);
+                if let Some(current) = current {
+                    if !current.directories.contains_key(&name) {
+                        self.warnings.push(LogWarning::UnlistedDirectory {
+                            line: self.line,
+                            name: name.clone(),
+                        });
+                    }
+                }
                 self.current_path.push(name);
             }
             ChangeDirectoryTarget::Out => {
                 if self.current_path.len() > 0 {
                     self.current_path.pop();
+                } else {
+                    self.warnings
+                        .push(LogWarning::OutOfRoot { line: self.line });
                 }
             }
             ChangeDirectoryTarget::Root => {
@@ -109,14 +278,32 @@ where
         }
     }
 
-    fn process_item(&mut self, directory_item: DirectoryItem) -> () {
+    fn process_item(&mut self, directory_item: DirectoryItem) {
+        match self.listing.as_mut() {
+            Some(listing) => listing.items.push(match &directory_item {
+                DirectoryItem::Directory { name } => format!("dir {}", name),
+                DirectoryItem::File { size, name } => format!("{} {}", size, name),
+            }),
+            None => self
+                .warnings
+                .push(LogWarning::ItemOutsideListing { line: self.line }),
+        }
+
+        // Items that were shown before are kept as they are, so that listing a directory again
+        // does not count its files twice
         if let Some(current) = self.file_system.get_directory_mut(&self.current_path) {
             match directory_item {
                 DirectoryItem::Directory { name } => {
-                    current.directories.insert(name, Directory::new());
+                    let path = current.child_path(&name);
+                    current
+                        .directories
+                        .entry(name)
+                        .or_insert_with(|| Directory::new(path));
                 }
                 DirectoryItem::File { size, name } => {
-                    current.files.push(File { size, name });
+                    if !current.files.iter().any(|file| file.name == name) {
+                        current.files.push(File { size, name });
+                    }
                 }
             }
         }
@@ -137,17 +324,31 @@ where
     }
 }
 
-impl FromIterator<CommandLine> for Directory {
-    fn from_iter<T: IntoIterator<Item = CommandLine>>(iter: T) -> Self {
-        let iter = iter.into_iter();
+impl Directory {
+    /// Reconstructs the file system from a terminal log, along with warnings about the log.
+    pub fn from_log<T: IntoIterator<Item = CommandLine>>(log: T) -> (Directory, Vec<LogWarning>) {
         let mut iter = CommandLineIterator {
-            source: iter,
-            file_system: Directory::new(),
+            source: log.into_iter(),
+            file_system: Directory::new(String::from("/")),
             current_path: Vec::new(),
+            line: 0,
+            listing: None,
+            listings: HashMap::new(),
+            warnings: Vec::new(),
         };
 
         while let Some(()) = iter.next() {}
+        iter.finish_listing();
+
+        let mut file_system = iter.file_system;
+        file_system.update_sizes();
+        (file_system, iter.warnings)
+    }
+}
 
-        iter.file_system
+impl FromIterator<CommandLine> for Directory {
+    fn from_iter<T: IntoIterator<Item = CommandLine>>(iter: T) -> Self {
+        let (file_system, _) = Directory::from_log(iter);
+        file_system
     }
 }
@@ -2,10 +2,13 @@ mod blueprint;
 mod factory;
 mod potential_production;
 mod potential_resources;
+#[cfg(test)]
+mod reference;
 mod resource_type;
 mod resources;
 
 use crate::{
+    common::{random::Random, threads},
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
 };
@@ -19,15 +22,16 @@ use wasm_bindgen::prelude::*;
 pub fn not_enough_minerals(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let ByLines(blueprints) = input.file_contents.parse::<ByLines<Blueprint>>()?;
     let answer = match input.puzzle_part {
-        PuzzlePart::Part1 => blueprints
+        PuzzlePart::Part1 => threads::parallel_map(blueprints, |b| b.id * b.factory().run(24))
             .into_iter()
-            .map(|b| b.id * b.factory().run(24))
             .sum::<usize>(),
-        PuzzlePart::Part2 => blueprints
+        PuzzlePart::Part2 => {
+            threads::parallel_map(blueprints.into_iter().take(take_count()).collect(), |b| {
+                b.factory().run(32)
+            })
             .into_iter()
-            .take(take_count())
-            .map(|b| b.factory().run(32))
-            .product::<usize>(),
+            .product::<usize>()
+        }
     };
     Ok(answer.to_string())
 }
@@ -42,9 +46,28 @@ fn take_count() -> usize {
     3
 }
 
+/// Generates `size` blueprints (at least 1) with robot costs in the same ranges as the puzzle.
+pub fn not_enough_minerals_generate(random: &mut Random, size: usize) -> String {
+    (1..=size.max(1))
+        .map(|id| {
+            format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.\n",
+                id,
+                random.range(2..5),
+                random.range(2..5),
+                random.range(2..5),
+                random.range(5..21),
+                random.range(2..5),
+                random.range(5..21)
+            )
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::property::{self, Integers, PuzzleInputs};
     use std::error::Error;
 
     const INPUT_TEXT: &str = "\
@@ -62,4 +85,36 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
         assert_eq!("33", output);
         Ok(())
     }
+
+    #[test]
+    fn factory_should_match_reference() {
+        let generator = (
+            PuzzleInputs {
+                generate: not_enough_minerals_generate,
+                sizes: 1..2,
+            },
+            Integers(10..17),
+        );
+        property::check_against_reference(
+            &generator,
+            |(file_contents, minutes)| {
+                let ByLines(blueprints) = file_contents.parse::<ByLines<Blueprint>>().ok()?;
+                Some(
+                    blueprints
+                        .iter()
+                        .map(|blueprint| blueprint.factory().run(*minutes as usize))
+                        .collect::<Vec<usize>>(),
+                )
+            },
+            |(file_contents, minutes)| {
+                let ByLines(blueprints) = file_contents.parse::<ByLines<Blueprint>>().ok()?;
+                Some(
+                    blueprints
+                        .iter()
+                        .map(|blueprint| reference::max_geodes(blueprint, *minutes as usize))
+                        .collect::<Vec<usize>>(),
+                )
+            },
+        );
+    }
 }
@@ -0,0 +1,62 @@
+use super::{blueprint::Blueprint, resource_type::ResourceType};
+use std::collections::HashMap;
+
+const RESOURCE_TYPES: [ResourceType; 4] = [
+    ResourceType::Ore,
+    ResourceType::Clay,
+    ResourceType::Obsidian,
+    ResourceType::Geode,
+];
+
+type State = (usize, [usize; 4], [usize; 4]);
+
+/// Finds the most geodes that can be opened, by trying every choice of robot (or none) in every
+/// minute, remembering the outcome of every state that was already visited.
+pub fn max_geodes(blueprint: &Blueprint, minutes: usize) -> usize {
+    let mut known: HashMap<State, usize> = HashMap::new();
+    max_geodes_from(blueprint, (minutes, [1, 0, 0, 0], [0, 0, 0, 0]), &mut known)
+}
+
+fn max_geodes_from(
+    blueprint: &Blueprint,
+    state: State,
+    known: &mut HashMap<State, usize>,
+) -> usize {
+    let (minutes, robots, stored) = state;
+    if minutes == 0 {
+        return stored[3];
+    }
+    if let Some(geodes) = known.get(&state) {
+        return *geodes;
+    }
+
+    let produced = |stored: [usize; 4]| {
+        let mut next = stored;
+        for index in 0..4 {
+            next[index] += robots[index];
+        }
+        next
+    };
+
+    let mut geodes = max_geodes_from(blueprint, (minutes - 1, robots, produced(stored)), known);
+    for (index, resource_type) in RESOURCE_TYPES.into_iter().enumerate() {
+        let cost = blueprint.cost(resource_type);
+        let cost = [cost.ore, cost.clay, cost.obsidian, cost.geode];
+        if (0..4).all(|resource| stored[resource] >= cost[resource]) {
+            let mut remaining = stored;
+            for resource in 0..4 {
+                remaining[resource] -= cost[resource];
+            }
+            let mut next_robots = robots;
+            next_robots[index] += 1;
+            geodes = geodes.max(max_geodes_from(
+                blueprint,
+                (minutes - 1, next_robots, produced(remaining)),
+                known,
+            ));
+        }
+    }
+
+    known.insert(state, geodes);
+    geodes
+}
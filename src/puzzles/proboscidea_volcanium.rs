@@ -1,5 +1,7 @@
 mod pair_crawler;
 mod pair_decision;
+#[cfg(test)]
+mod reference;
 mod solo_crawler;
 mod solo_decision;
 mod target_valve;
@@ -7,6 +9,7 @@ mod valve;
 mod valve_system;
 
 use crate::{
+    common::{random::Random, threads},
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
 };
@@ -29,14 +32,86 @@ pub fn proboscidea_volcanium(input: PuzzleInput) -> Result<String, ParseContents
     let valve_system: ValveSystem = valves.into();
     let answer = match input.puzzle_part {
         PuzzlePart::Part1 => valve_system.solo_crawler().max().map_or(0, |x| x),
-        PuzzlePart::Part2 => valve_system.pair_crawler().max().map_or(0, |x| x),
+        PuzzlePart::Part2 => {
+            let branch_count = threads::thread_count();
+            let branches = (0..branch_count).collect::<Vec<usize>>();
+            threads::parallel_map(branches, |branch| {
+                valve_system
+                    .pair_crawler_branch(branch, branch_count)
+                    .max()
+                    .map_or(0, |x| x)
+            })
+            .into_iter()
+            .max()
+            .map_or(0, |x| x)
+        }
     };
     Ok(answer.to_string())
 }
 
+/// Generates a connected network of `size` valves (at least 2), including the starting valve AA.
+/// About a third of the valves (at most 15) have a positive flow rate.
+pub fn proboscidea_volcanium_generate(random: &mut Random, size: usize) -> String {
+    let size = size.clamp(2, 26 * 26);
+    let mut names = (0..(26 * 26))
+        .map(|index| {
+            let first = char::from(b'A' + (index / 26) as u8);
+            let second = char::from(b'A' + (index % 26) as u8);
+            format!("{}{}", first, second)
+        })
+        .collect::<Vec<String>>();
+    random.shuffle(&mut names[1..]);
+
+    // A random spanning tree keeps the network connected, with some extra tunnels on top
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); size];
+    for index in 1..size {
+        let other = random.below(index);
+        tunnels[index].push(other);
+        tunnels[other].push(index);
+    }
+    for _ in 0..(size / 4) {
+        let from = random.below(size);
+        let to = random.below(size);
+        if from != to && !tunnels[from].contains(&to) {
+            tunnels[from].push(to);
+            tunnels[to].push(from);
+        }
+    }
+
+    let mut flow_rates = vec![0; size];
+    let mut valves_with_flow = (1..size).collect::<Vec<usize>>();
+    random.shuffle(&mut valves_with_flow);
+    for index in valves_with_flow.into_iter().take((size / 3).clamp(1, 15)) {
+        flow_rates[index] = random.range(1..26);
+    }
+
+    (0..size)
+        .map(|index| {
+            let targets = tunnels[index]
+                .iter()
+                .map(|target| names[*target].as_str())
+                .collect::<Vec<&str>>();
+            if targets.len() == 1 {
+                format!(
+                    "Valve {} has flow rate={}; tunnel leads to valve {}\n",
+                    names[index], flow_rates[index], targets[0]
+                )
+            } else {
+                format!(
+                    "Valve {} has flow rate={}; tunnels lead to valves {}\n",
+                    names[index],
+                    flow_rates[index],
+                    targets.join(", ")
+                )
+            }
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::property::{self, PuzzleInputs};
     use std::error::Error;
 
     const INPUT_TEXT: &str = "\
@@ -73,4 +148,54 @@ Valve JJ has flow rate=21; tunnel leads to valve II
         assert_eq!("1707", output);
         Ok(())
     }
+
+    #[test]
+    fn crawlers_should_match_reference() {
+        let generator = PuzzleInputs {
+            generate: proboscidea_volcanium_generate,
+            sizes: 2..8,
+        };
+        property::check_against_reference(
+            &generator,
+            |file_contents| {
+                [PuzzlePart::Part1, PuzzlePart::Part2]
+                    .into_iter()
+                    .map(|puzzle_part| {
+                        proboscidea_volcanium(PuzzleInput {
+                            file_contents: file_contents.clone(),
+                            puzzle_part,
+                        })
+                        .ok()
+                    })
+                    .collect::<Vec<Option<String>>>()
+            },
+            |file_contents| {
+                let valves = file_contents
+                    .parse::<ByLines<Valve>>()
+                    .ok()
+                    .map(|ByLines(valves)| valves);
+                [(30, 1), (26, 2)]
+                    .into_iter()
+                    .map(|(minutes, actor_count)| {
+                        valves.as_ref().map(|valves| {
+                            reference::max_pressure(valves, minutes, actor_count).to_string()
+                        })
+                    })
+                    .collect::<Vec<Option<String>>>()
+            },
+        );
+    }
+
+    #[test]
+    fn example_2_with_threads() -> Result<(), Box<dyn Error>> {
+        let output = threads::with_thread_count(3, || {
+            proboscidea_volcanium(PuzzleInput {
+                file_contents: INPUT_TEXT.to_string(),
+                puzzle_part: PuzzlePart::Part2,
+            })
+        });
+
+        assert_eq!("1707", output?);
+        Ok(())
+    }
 }
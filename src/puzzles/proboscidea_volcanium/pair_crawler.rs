@@ -5,6 +5,8 @@ use super::{
 pub struct PairCrawler<'a> {
     valve_system: &'a ValveSystem,
     decisions: Vec<PairDecision>,
+    branch: usize,
+    branch_count: usize,
     calls: usize,
     current_max: usize,
 }
@@ -22,7 +24,9 @@ impl<'a> PairCrawler<'a> {
 
     fn populate(&mut self, current: Option<(PairDecision, TargetValvePair)>) -> usize {
         let mut decision = match current {
-            None => PairDecision::new(self.valve_system),
+            None => {
+                PairDecision::new(self.valve_system).only_branch(self.branch, self.branch_count)
+            }
             Some((latest_decision, target_valve)) => {
                 let new_decision = latest_decision
                     .move_to_valve_and_open(&self.valve_system.distance, &target_valve);
@@ -49,8 +53,11 @@ impl<'a> Iterator for PairCrawler<'a> {
         self.calls += 1;
         let result = if self.decisions.len() > 0 {
             self.next_decision()
-        } else {
+        } else if self.calls == 1 {
             Some(self.populate(None))
+        } else {
+            // A branch without any targets is done after its first decision
+            None
         };
         if let Some(new_result) = result {
             if new_result > self.current_max {
@@ -65,14 +72,17 @@ impl<'a> Iterator for PairCrawler<'a> {
 }
 
 pub trait AsPairCrawler {
-    fn pair_crawler<'a>(&'a self) -> PairCrawler<'a>;
+    /// A crawler that only explores every `branch_count`-th first decision, starting at `branch`.
+    fn pair_crawler_branch<'a>(&'a self, branch: usize, branch_count: usize) -> PairCrawler<'a>;
 }
 
 impl AsPairCrawler for ValveSystem {
-    fn pair_crawler<'a>(&'a self) -> PairCrawler<'a> {
+    fn pair_crawler_branch<'a>(&'a self, branch: usize, branch_count: usize) -> PairCrawler<'a> {
         PairCrawler {
             valve_system: self,
             decisions: Vec::new(),
+            branch,
+            branch_count,
             calls: 0,
             current_max: 0,
         }
@@ -22,6 +22,20 @@ impl Iterator for PairDecision {
 }
 
 impl PairDecision {
+    /// Only keeps every `branch_count`-th target, starting at `branch`, so that the targets can be
+    /// divided among several crawlers.
+    pub fn only_branch(self, branch: usize, branch_count: usize) -> Self {
+        PairDecision {
+            targets: Box::new(
+                self.targets
+                    .enumerate()
+                    .filter(move |(index, _)| index % branch_count == branch)
+                    .map(|(_, target)| target),
+            ),
+            ..self
+        }
+    }
+
     pub fn new(valve_system: &ValveSystem) -> Self {
         let time_left = 26;
         let pressure_released = 0;
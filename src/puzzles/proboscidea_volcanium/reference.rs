@@ -0,0 +1,95 @@
+use super::valve::Valve;
+use std::collections::HashMap;
+
+type State = (usize, Vec<usize>, u64);
+
+/// Finds the most pressure that the given number of actors can release, by trying every
+/// combination of moving, opening a valve, or waiting in every minute, remembering the outcome of
+/// every state that was already visited.
+pub fn max_pressure(valves: &[Valve], minutes: usize, actor_count: usize) -> usize {
+    let indices = valves
+        .iter()
+        .enumerate()
+        .map(|(index, valve)| (valve.name.as_str(), index))
+        .collect::<HashMap<&str, usize>>();
+    let tunnels = valves
+        .iter()
+        .map(|valve| {
+            valve
+                .connected_to
+                .iter()
+                .filter_map(|name| indices.get(name.as_str()).copied())
+                .collect::<Vec<usize>>()
+        })
+        .collect::<Vec<Vec<usize>>>();
+    let start = match indices.get("AA") {
+        Some(start) => *start,
+        None => return 0,
+    };
+
+    let mut known: HashMap<State, usize> = HashMap::new();
+    max_pressure_from(
+        valves,
+        &tunnels,
+        (minutes, vec![start; actor_count], 0),
+        &mut known,
+    )
+}
+
+fn max_pressure_from(
+    valves: &[Valve],
+    tunnels: &[Vec<usize>],
+    state: State,
+    known: &mut HashMap<State, usize>,
+) -> usize {
+    let (minutes, positions, opened) = &state;
+    if *minutes == 0 {
+        return 0;
+    }
+    if let Some(pressure) = known.get(&state) {
+        return *pressure;
+    }
+
+    // Every actor either opens the valve it is at, or moves through a tunnel, or stays put
+    let mut outcomes: Vec<(Vec<usize>, u64, usize)> = vec![(Vec::new(), *opened, 0)];
+    for position in positions.iter() {
+        let mut next_outcomes: Vec<(Vec<usize>, u64, usize)> = Vec::new();
+        for (next_positions, next_opened, pressure) in outcomes {
+            let valve = 1 << position;
+            if valves[*position].flow_rate > 0 && next_opened & valve == 0 {
+                let mut opened_positions = next_positions.clone();
+                opened_positions.push(*position);
+                next_outcomes.push((
+                    opened_positions,
+                    next_opened | valve,
+                    pressure + valves[*position].flow_rate * (minutes - 1),
+                ));
+            }
+            for target in tunnels[*position].iter().chain([position]) {
+                let mut moved_positions = next_positions.clone();
+                moved_positions.push(*target);
+                next_outcomes.push((moved_positions, next_opened, pressure));
+            }
+        }
+        outcomes = next_outcomes;
+    }
+
+    let best = outcomes
+        .into_iter()
+        .map(|(mut next_positions, next_opened, pressure)| {
+            // The actors are interchangeable, so their order does not matter
+            next_positions.sort();
+            pressure
+                + max_pressure_from(
+                    valves,
+                    tunnels,
+                    (minutes - 1, next_positions, next_opened),
+                    known,
+                )
+        })
+        .max()
+        .map_or(0, |x| x);
+
+    known.insert(state, best);
+    best
+}
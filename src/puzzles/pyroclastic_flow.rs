@@ -4,6 +4,7 @@ mod rock_simulator;
 
 use self::{rock_shift::RockShiftCollection, rock_simulator::AsRockSimulator};
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::error::ParseContentsError,
 };
@@ -99,6 +100,13 @@ fn determine_cycle(collection: &RockShiftCollection) -> CycleInfo {
     unreachable!("infinite iterator");
 }
 
+/// Generates a jet pattern of `size` pushes (at least 1).
+pub fn pyroclastic_flow_generate(random: &mut Random, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| *random.choose(&['<', '>']))
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
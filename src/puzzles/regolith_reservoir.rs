@@ -2,6 +2,7 @@ mod cave_in;
 mod rock_range;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::{error::ParseContentsError, lines::ByLines},
 };
@@ -30,6 +31,37 @@ pub fn regolith_reservoir(input: PuzzleInput) -> Result<String, ParseContentsErr
     Ok(answer.to_string())
 }
 
+/// Generates `size` paths of rock, each consisting of alternating horizontal and vertical
+/// segments below the source of the sand.
+pub fn regolith_reservoir_generate(random: &mut Random, size: usize) -> String {
+    let depth = 10 + size as i64 / 2;
+    (0..size)
+        .map(|_| {
+            let mut x = random.range((500 - depth)..(500 + depth));
+            let mut y = random.range(2..depth);
+            let mut points = vec![format!("{},{}", x, y)];
+            let mut horizontal = random.chance(1, 2);
+            for _ in 0..random.range(1..5) {
+                let (value, min, max) = if horizontal {
+                    (&mut x, 500 - depth, 500 + depth)
+                } else {
+                    (&mut y, 2, depth)
+                };
+                let mut next = *value;
+                while next == *value {
+                    next = (*value + random.range(-6..7)).clamp(min, max);
+                }
+                *value = next;
+                points.push(format!("{},{}", x, y));
+                horizontal = !horizontal;
+            }
+            let mut line = points.join(" -> ");
+            line.push('\n');
+            line
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
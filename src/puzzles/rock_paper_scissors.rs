@@ -1,15 +1,18 @@
+mod guide;
 mod rps_match;
+mod rule_set;
 mod scorable;
 mod strategy;
 
-use self::{
-    rps_match::{RpsMatch, RpsTargetMap},
-    scorable::Scorable,
-    strategy::RpsStrategy,
-};
+use self::{guide::Guide, rule_set::RpsRuleSet, scorable::Scorable};
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
-    parse::{error::ParseContentsError, lines::ByLines},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
+    parse::error::ParseContentsError,
 };
 
 #[cfg(feature = "wasm")]
@@ -19,7 +22,7 @@ use wasm_bindgen::prelude::*;
 #[wasm_bindgen]
 pub fn rock_paper_scissors_validate(input: JsValue) -> JsValue {
     let input: String = serde_wasm_bindgen::from_value(input).unwrap();
-    let result = match input.parse::<ByLines<RpsStrategy>>() {
+    let result = match RpsRuleSet::rock_paper_scissors().parse_strategies(&input) {
         Ok(_) => None,
         Err(error) => Some(error),
     };
@@ -28,18 +31,74 @@ pub fn rock_paper_scissors_validate(input: JsValue) -> JsValue {
 
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn rock_paper_scissors(input: PuzzleInput) -> Result<String, ParseContentsError> {
-    let ByLines(strategy) = input.file_contents.parse::<ByLines<RpsStrategy>>()?;
-    let rps_target_map = RpsTargetMap::new();
-    let interpretation: Box<dyn Fn(RpsStrategy) -> RpsMatch> = match input.puzzle_part {
-        PuzzlePart::Part1 => Box::new(|strategy| rps_target_map.map_target_as_type(strategy)),
-        PuzzlePart::Part2 => Box::new(|strategy| rps_target_map.map_target_as_result(strategy)),
+    let rule_set = RpsRuleSet::rock_paper_scissors();
+    let answer = total_score(&rule_set, &input.file_contents, input.puzzle_part)?;
+    Ok(answer.to_string())
+}
+
+/// Reports on the strategy guide, under a rule set given as the last argument: `rps` (the default),
+/// `rpsls`, or `cyclic-<shape count>`. The modes are:
+/// - `score <part> [rule set]`: the total score of a part.
+/// - `optimal [rule set]`: the maximum score against the opponent column, compared to both parts.
+/// - `suboptimal <part> [rule set]`: the lines on which a better shape could have been chosen.
+/// - `compare [rule set]`: the matches of both parts side by side, line by line.
+pub fn rock_paper_scissors_report(input: ReportInput) -> Result<String, ReportError> {
+    let rule_set_argument = |index: usize| -> Result<RpsRuleSet, ReportError> {
+        Ok(input
+            .argument::<RpsRuleSet>(index, "rule set")?
+            .unwrap_or_else(RpsRuleSet::rock_paper_scissors))
     };
-    let answer = strategy
+    match input.mode.as_str() {
+        "score" => {
+            let puzzle_part = input.required_argument::<PuzzlePart>(0, "part")?;
+            let rule_set = rule_set_argument(1)?;
+            let score = total_score(&rule_set, &input.file_contents, puzzle_part)?;
+            Ok(format!("{}\n", score))
+        }
+        "optimal" => {
+            let rule_set = rule_set_argument(0)?;
+            let guide = Guide::parse(&rule_set, &input.file_contents)?;
+            Ok(guide.maximum_score_report())
+        }
+        "suboptimal" => {
+            let puzzle_part = input.required_argument::<PuzzlePart>(0, "part")?;
+            let rule_set = rule_set_argument(1)?;
+            let guide = Guide::parse(&rule_set, &input.file_contents)?;
+            Ok(guide.suboptimal_report(puzzle_part)?)
+        }
+        "compare" => {
+            let rule_set = rule_set_argument(0)?;
+            let guide = Guide::parse(&rule_set, &input.file_contents)?;
+            Ok(guide.comparison_report())
+        }
+        _ => Err(input.unknown_mode(&["score", "optimal", "suboptimal", "compare"])),
+    }
+}
+
+fn total_score(
+    rule_set: &RpsRuleSet,
+    file_contents: &str,
+    puzzle_part: PuzzlePart,
+) -> Result<i32, ParseContentsError> {
+    let guide = Guide::parse(rule_set, file_contents)?;
+    Ok(guide
+        .matches(puzzle_part)?
         .into_iter()
-        .map(interpretation)
         .map(|x| x.score())
-        .sum::<i32>();
-    Ok(answer.to_string())
+        .sum::<i32>())
+}
+
+/// Generates a strategy guide of `size` rounds.
+pub fn rock_paper_scissors_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                random.choose(&["A", "B", "C"]),
+                random.choose(&["X", "Y", "Z"])
+            )
+        })
+        .collect::<String>()
 }
 
 #[cfg(test)]
@@ -75,4 +134,115 @@ C Z
         assert_eq!("12", output);
         Ok(())
     }
+
+    const RPSLS_INPUT_TEXT: &str = "\
+A X
+E Z
+C Y
+";
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_1() -> Result<(), Box<dyn Error>> {
+        let rule_set = RpsRuleSet::rock_paper_scissors_lizard_spock();
+
+        assert_eq!(
+            15,
+            total_score(&rule_set, RPSLS_INPUT_TEXT, PuzzlePart::Part1)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_2() -> Result<(), Box<dyn Error>> {
+        let rule_set = RpsRuleSet::rock_paper_scissors_lizard_spock();
+
+        assert_eq!(
+            17,
+            total_score(&rule_set, RPSLS_INPUT_TEXT, PuzzlePart::Part2)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cyclic_game_of_three_should_be_rock_paper_scissors() -> Result<(), Box<dyn Error>> {
+        let rule_set = RpsRuleSet::cyclic(3)?;
+
+        assert_eq!(15, total_score(&rule_set, INPUT_TEXT, PuzzlePart::Part1)?);
+        assert_eq!(12, total_score(&rule_set, INPUT_TEXT, PuzzlePart::Part2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn letters_should_be_validated_against_rule_set() {
+        let rule_set = RpsRuleSet::rock_paper_scissors();
+        let error = total_score(&rule_set, "A Y\nE V\n", PuzzlePart::Part1).unwrap_err();
+
+        assert_eq!(
+            "line 1: opponent choice invalid: 'E' is not in expected format 'A|B|C'\n\
+             target invalid: 'V' is not in expected format 'X|Y|Z'\n\n\n",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn optimal_should_compare_parts_with_maximum() -> Result<(), Box<dyn Error>> {
+        let output = rock_paper_scissors_report(ReportInput::new("optimal", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+maximum score: 24
+part 1 score: 15 (9 short of the maximum)
+part 2 score: 12 (12 short of the maximum)
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn optimal_should_explain_unavailable_parts() -> Result<(), Box<dyn Error>> {
+        let output = rock_paper_scissors_report(ReportInput::new("optimal", &["rpsls"], "A V\n"))?;
+
+        assert_eq!(
+            "\
+maximum score: 11
+part 1 score: 4 (7 short of the maximum)
+part 2 score unavailable: line 1: 'V' does not stand for a result
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn suboptimal_should_list_lines_with_better_shapes() -> Result<(), Box<dyn Error>> {
+        let output =
+            rock_paper_scissors_report(ReportInput::new("suboptimal", &["1"], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+line 2 (B X): rock vs paper, loss: 1, but scissors vs paper, win: 9
+line 3 (C Z): scissors vs scissors, draw: 6, but rock vs scissors, win: 7
+2 of 3 lines are suboptimal, losing 9 points
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compare_should_show_both_parts_per_line() -> Result<(), Box<dyn Error>> {
+        let output = rock_paper_scissors_report(ReportInput::new("compare", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+line  guide  part 1                         part 2                    change
+   1  A Y    paper vs rock, win: 8          rock vs rock, draw: 4     -4
+   2  B X    rock vs paper, loss: 1         rock vs paper, loss: 1    +0
+   3  C Z    scissors vs scissors, draw: 6  rock vs scissors, win: 7  +1
+",
+            output
+        );
+        Ok(())
+    }
 }
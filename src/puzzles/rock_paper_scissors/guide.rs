@@ -0,0 +1,184 @@
+use std::fmt::Write;
+
+use crate::{
+    input::puzzle_part::PuzzlePart,
+    parse::{
+        error::ParseContentsError,
+        lines::{ByLines, ParseByLineError},
+    },
+};
+
+use super::{
+    rps_match::{RpsMatch, RpsTargetError, RpsTargetMap},
+    rule_set::RpsRuleSet,
+    scorable::Scorable,
+    strategy::RpsStrategy,
+};
+
+/// A strategy guide together with the rule set that it is read with.
+pub struct Guide<'a> {
+    rps_target_map: RpsTargetMap<'a>,
+    lines: Vec<String>,
+    strategy: Vec<RpsStrategy>,
+}
+
+impl<'a> Guide<'a> {
+    pub fn parse(
+        rule_set: &'a RpsRuleSet,
+        file_contents: &str,
+    ) -> Result<Self, ParseContentsError> {
+        let ByLines(strategy) = rule_set.parse_strategies(file_contents)?;
+
+        Ok(Guide {
+            rps_target_map: RpsTargetMap::new(rule_set),
+            lines: file_contents
+                .lines()
+                .map(|line| line.trim().to_string())
+                .collect(),
+            strategy,
+        })
+    }
+
+    fn part_match(
+        &self,
+        strategy: RpsStrategy,
+        puzzle_part: PuzzlePart,
+    ) -> Result<RpsMatch<'a>, RpsTargetError> {
+        match puzzle_part {
+            PuzzlePart::Part1 => Ok(self.rps_target_map.map_target_as_type(strategy)),
+            PuzzlePart::Part2 => self.rps_target_map.map_target_as_result(strategy),
+        }
+    }
+
+    pub fn matches(
+        &self,
+        puzzle_part: PuzzlePart,
+    ) -> Result<Vec<RpsMatch<'a>>, ParseContentsError> {
+        self.strategy
+            .iter()
+            .enumerate()
+            .map(|(line, strategy)| {
+                self.part_match(*strategy, puzzle_part).map_err(|error| {
+                    ParseContentsError::new(ParseByLineError::new(line + 1, error))
+                })
+            })
+            .collect()
+    }
+
+    fn best_matches(&self) -> Vec<RpsMatch<'a>> {
+        self.strategy
+            .iter()
+            .map(|strategy| self.rps_target_map.best_match(strategy.opponent_choice))
+            .collect()
+    }
+
+    /// Compares the maximum score against the opponent column with the scores of both parts.
+    pub fn maximum_score_report(&self) -> String {
+        let maximum_score = self.best_matches().iter().map(|x| x.score()).sum::<i32>();
+
+        let mut output = String::new();
+        writeln!(output, "maximum score: {}", maximum_score).unwrap();
+        for (puzzle_part, part_number) in [(PuzzlePart::Part1, 1), (PuzzlePart::Part2, 2)] {
+            match self.matches(puzzle_part) {
+                Ok(matches) => {
+                    let score = matches.iter().map(|x| x.score()).sum::<i32>();
+                    writeln!(
+                        output,
+                        "part {} score: {} ({} short of the maximum)",
+                        part_number,
+                        score,
+                        maximum_score - score
+                    )
+                    .unwrap();
+                }
+                Err(error) => writeln!(
+                    output,
+                    "part {} score unavailable: {}",
+                    part_number,
+                    error.to_string().trim_end()
+                )
+                .unwrap(),
+            }
+        }
+        output
+    }
+
+    /// Lists the lines on which another shape would have scored more, with the best shape.
+    pub fn suboptimal_report(&self, puzzle_part: PuzzlePart) -> Result<String, ParseContentsError> {
+        let matches = self.matches(puzzle_part)?;
+        let best_matches = self.best_matches();
+
+        let mut output = String::new();
+        let mut suboptimal_count: usize = 0;
+        let mut lost_score: i32 = 0;
+        for (index, (rps_match, best_match)) in matches.iter().zip(best_matches.iter()).enumerate()
+        {
+            if rps_match.score() < best_match.score() {
+                suboptimal_count += 1;
+                lost_score += best_match.score() - rps_match.score();
+                writeln!(
+                    output,
+                    "line {} ({}): {}, but {}",
+                    index + 1,
+                    self.lines[index],
+                    rps_match,
+                    best_match
+                )
+                .unwrap();
+            }
+        }
+        writeln!(
+            output,
+            "{} of {} lines are suboptimal, losing {} points",
+            suboptimal_count,
+            matches.len(),
+            lost_score
+        )
+        .unwrap();
+        Ok(output)
+    }
+
+    /// Shows the match of every line when read as in part 1 and as in part 2, with the change in
+    /// score. Lines that cannot be read as in part 2 are marked as such.
+    pub fn comparison_report(&self) -> String {
+        let rows = self
+            .strategy
+            .iter()
+            .map(|strategy| {
+                let part_1 = self.rps_target_map.map_target_as_type(*strategy);
+                match self.rps_target_map.map_target_as_result(*strategy) {
+                    Ok(part_2) => (
+                        part_1.to_string(),
+                        part_2.to_string(),
+                        format!("{:+}", part_2.score() - part_1.score()),
+                    ),
+                    Err(error) => (part_1.to_string(), error.to_string(), String::new()),
+                }
+            })
+            .collect::<Vec<(String, String, String)>>();
+        let guide_width = self.lines.iter().map(String::len).max().unwrap_or(0).max(5);
+        let part_1_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0).max(6);
+        let part_2_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0).max(6);
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            "line  {:<guide_width$}  {:<part_1_width$}  {:<part_2_width$}  change",
+            "guide", "part 1", "part 2"
+        )
+        .unwrap();
+        for (index, (part_1, part_2, change)) in rows.iter().enumerate() {
+            writeln!(
+                output,
+                "{:>4}  {:<guide_width$}  {:<part_1_width$}  {:<part_2_width$}  {}",
+                index + 1,
+                self.lines[index],
+                part_1,
+                part_2,
+                change
+            )
+            .unwrap();
+        }
+        output
+    }
+}
@@ -1,6 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
 
-use super::strategy::{RpsStrategy, RpsTarget, RpsType};
+use super::{
+    rule_set::{RpsRuleSet, RpsShape},
+    scorable::Scorable,
+    strategy::{RpsStrategy, RpsType},
+};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum RpsResult {
@@ -9,126 +17,148 @@ pub enum RpsResult {
     Win,
 }
 
-pub struct RpsMatch {
-    pub opponent_choice: RpsType,
-    pub own_choice: RpsType,
+impl Display for RpsResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Loss => write!(f, "loss"),
+            Self::Draw => write!(f, "draw"),
+            Self::Win => write!(f, "win"),
+        }
+    }
+}
+
+pub struct RpsMatch<'a> {
+    pub opponent_choice: &'a RpsShape,
+    pub own_choice: &'a RpsShape,
     pub result: RpsResult,
 }
 
-impl From<RpsTarget> for RpsResult {
-    fn from(value: RpsTarget) -> Self {
-        match value {
-            RpsTarget::X => RpsResult::Loss,
-            RpsTarget::Y => RpsResult::Draw,
-            RpsTarget::Z => RpsResult::Win,
+impl Display for RpsMatch<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} vs {}, {}: {}",
+            self.own_choice.name,
+            self.opponent_choice.name,
+            self.result,
+            self.score()
+        )
+    }
+}
+
+pub enum RpsTargetError {
+    NotAResult(char),
+    NoChoice {
+        opponent_choice: String,
+        result: RpsResult,
+    },
+}
+
+impl Display for RpsTargetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAResult(letter) => write!(f, "'{}' does not stand for a result", letter),
+            Self::NoChoice {
+                opponent_choice,
+                result,
+            } => write!(
+                f,
+                "no shape gives a {} against '{}'",
+                result, opponent_choice
+            ),
         }
     }
 }
 
-pub struct RpsMatchResultMap {
-    pub opponent_choice: RpsType,
-    pub own_choice: RpsType,
-    pub result: RpsResult,
+impl Debug for RpsTargetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
 }
 
-pub const RPS_MATCH_RESULT_MAPPING: [RpsMatchResultMap; 9] = [
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Rock,
-        own_choice: RpsType::Rock,
-        result: RpsResult::Draw,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Rock,
-        own_choice: RpsType::Paper,
-        result: RpsResult::Win,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Rock,
-        own_choice: RpsType::Scissors,
-        result: RpsResult::Loss,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Paper,
-        own_choice: RpsType::Rock,
-        result: RpsResult::Loss,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Paper,
-        own_choice: RpsType::Paper,
-        result: RpsResult::Draw,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Paper,
-        own_choice: RpsType::Scissors,
-        result: RpsResult::Win,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Scissors,
-        own_choice: RpsType::Rock,
-        result: RpsResult::Win,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Scissors,
-        own_choice: RpsType::Paper,
-        result: RpsResult::Loss,
-    },
-    RpsMatchResultMap {
-        opponent_choice: RpsType::Scissors,
-        own_choice: RpsType::Scissors,
-        result: RpsResult::Draw,
-    },
-];
+impl Error for RpsTargetError {}
 
-pub struct RpsTargetMap {
+pub struct RpsTargetMap<'a> {
+    rule_set: &'a RpsRuleSet,
     to_result_map: HashMap<(RpsType, RpsType), RpsResult>,
     to_own_choice_map: HashMap<(RpsType, RpsResult), RpsType>,
 }
 
-impl RpsTargetMap {
-    pub fn new() -> Self {
+impl<'a> RpsTargetMap<'a> {
+    /// Builds the maps for a rule set. If several shapes give the same result against a shape, the
+    /// first of them is chosen when targets are read as results.
+    pub fn new(rule_set: &'a RpsRuleSet) -> Self {
         let mut to_result_map: HashMap<(RpsType, RpsType), RpsResult> = HashMap::new();
         let mut to_own_choice_map: HashMap<(RpsType, RpsResult), RpsType> = HashMap::new();
 
-        for mapping in RPS_MATCH_RESULT_MAPPING {
-            to_result_map.insert(
-                (mapping.opponent_choice, mapping.own_choice),
-                mapping.result,
-            );
-            to_own_choice_map.insert(
-                (mapping.opponent_choice, mapping.result),
-                mapping.own_choice,
-            );
+        for opponent_choice in rule_set.types() {
+            for own_choice in rule_set.types() {
+                let result = rule_set.result(opponent_choice, own_choice);
+                to_result_map.insert((opponent_choice, own_choice), result);
+                to_own_choice_map
+                    .entry((opponent_choice, result))
+                    .or_insert(own_choice);
+            }
         }
 
         RpsTargetMap {
+            rule_set,
             to_result_map,
             to_own_choice_map,
         }
     }
 
-    pub fn map_target_as_type(&self, strategy: RpsStrategy) -> RpsMatch {
-        let opponent_choice = strategy.opponent_choice;
-        let own_choice: RpsType = strategy.target.into();
+    pub fn map_target_as_type(&self, strategy: RpsStrategy) -> RpsMatch<'a> {
+        let own_choice = self.rule_set.target_as_type(strategy.target);
+        self.create_match(strategy.opponent_choice, own_choice)
+    }
+
+    /// Returns the match with the highest score against the opponent choice, or the first of them
+    /// if several shapes score as high.
+    pub fn best_match(&self, opponent_choice: RpsType) -> RpsMatch<'a> {
+        self.rule_set
+            .types()
+            .map(|own_choice| self.create_match(opponent_choice, own_choice))
+            .reduce(|best, rps_match| {
+                if rps_match.score() > best.score() {
+                    rps_match
+                } else {
+                    best
+                }
+            })
+            .expect("rule sets have at least one shape")
+    }
+
+    fn create_match(&self, opponent_choice: RpsType, own_choice: RpsType) -> RpsMatch<'a> {
         match self.to_result_map.get(&(opponent_choice, own_choice)) {
             Some(result) => RpsMatch {
-                opponent_choice,
-                own_choice,
+                opponent_choice: self.rule_set.shape(opponent_choice),
+                own_choice: self.rule_set.shape(own_choice),
                 result: *result,
             },
             None => unreachable!("by definition of mapping"),
         }
     }
 
-    pub fn map_target_as_result(&self, strategy: RpsStrategy) -> RpsMatch {
+    pub fn map_target_as_result(
+        &self,
+        strategy: RpsStrategy,
+    ) -> Result<RpsMatch<'a>, RpsTargetError> {
         let opponent_choice = strategy.opponent_choice;
-        let result: RpsResult = strategy.target.into();
+        let result = self
+            .rule_set
+            .target_as_result(strategy.target)
+            .ok_or_else(|| RpsTargetError::NotAResult(self.rule_set.own_letter(strategy.target)))?;
         match self.to_own_choice_map.get(&(opponent_choice, result)) {
-            Some(own_choice) => RpsMatch {
-                opponent_choice,
-                own_choice: *own_choice,
+            Some(own_choice) => Ok(RpsMatch {
+                opponent_choice: self.rule_set.shape(opponent_choice),
+                own_choice: self.rule_set.shape(*own_choice),
                 result,
-            },
-            None => unreachable!("by definition of mapping"),
+            }),
+            None => Err(RpsTargetError::NoChoice {
+                opponent_choice: self.rule_set.shape(opponent_choice).name.clone(),
+                result,
+            }),
         }
     }
 }
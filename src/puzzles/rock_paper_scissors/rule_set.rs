@@ -0,0 +1,328 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::parse::lines::{ByLines, ParseByLinesError};
+
+use super::{
+    rps_match::RpsResult,
+    strategy::{ParseRpsStrategyError, RpsStrategy, RpsTarget, RpsType},
+};
+
+#[derive(Debug)]
+pub struct RpsShape {
+    pub name: String,
+    pub score: i32,
+}
+
+pub enum RpsRuleSetError {
+    UnknownRuleSet(String),
+    NoShapes,
+    CyclicShapeCount(usize),
+    AlphabetSize {
+        column: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    DuplicateLetter {
+        column: &'static str,
+        letter: char,
+    },
+    UnknownResultLetter(char),
+    UnknownShape(String),
+    BeatsItself(String),
+    BeatEachOther(String, String),
+}
+
+impl Display for RpsRuleSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRuleSet(name) => write!(f, "unknown rule set '{}'", name),
+            Self::NoShapes => write!(f, "a rule set needs at least one shape"),
+            Self::CyclicShapeCount(count) => write!(
+                f,
+                "a cyclic game needs an odd number of shapes from 3 to 13, not {}",
+                count
+            ),
+            Self::AlphabetSize {
+                column,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "the {} alphabet has {} letters instead of {}",
+                column, actual, expected
+            ),
+            Self::DuplicateLetter { column, letter } => {
+                write!(f, "the {} alphabet has '{}' more than once", column, letter)
+            }
+            Self::UnknownResultLetter(letter) => {
+                write!(f, "result letter '{}' is not in the own alphabet", letter)
+            }
+            Self::UnknownShape(name) => write!(f, "unknown shape '{}'", name),
+            Self::BeatsItself(name) => write!(f, "'{}' beats itself", name),
+            Self::BeatEachOther(first, second) => {
+                write!(f, "'{}' and '{}' beat each other", first, second)
+            }
+        }
+    }
+}
+
+impl Debug for RpsRuleSetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for RpsRuleSetError {}
+
+impl FromStr for RpsRuleSet {
+    type Err = RpsRuleSetError;
+
+    /// Parses `rps`, `rpsls` or `cyclic-<shape count>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rps" => Ok(Self::rock_paper_scissors()),
+            "rpsls" => Ok(Self::rock_paper_scissors_lizard_spock()),
+            _ => match s
+                .strip_prefix("cyclic-")
+                .map(|count| count.parse::<usize>())
+            {
+                Some(Ok(shape_count)) => Self::cyclic(shape_count),
+                _ => Err(Self::Err::UnknownRuleSet(s.to_string())),
+            },
+        }
+    }
+}
+
+/// The rules of a game like rock paper scissors: its shapes with their scores, which shape beats
+/// which, and the letters that stand for them in strategy guides.
+pub struct RpsRuleSet {
+    shapes: Vec<RpsShape>,
+    beats: HashSet<(RpsType, RpsType)>,
+    opponent_letters: Vec<char>,
+    own_letters: Vec<char>,
+    /// The targets standing for a loss, a draw and a win, in that order.
+    result_targets: [RpsTarget; 3],
+}
+
+impl RpsRuleSet {
+    /// Builds a rule set from shapes, pairs of shape names in which the first beats the second, and
+    /// alphabets with a letter per shape. The result letters stand for a loss, a draw and a win, and
+    /// have to be in the own alphabet.
+    pub fn new(
+        shapes: Vec<RpsShape>,
+        beats: &[(&str, &str)],
+        opponent_letters: &str,
+        own_letters: &str,
+        result_letters: &str,
+    ) -> Result<RpsRuleSet, RpsRuleSetError> {
+        if shapes.is_empty() {
+            return Err(RpsRuleSetError::NoShapes);
+        }
+        let opponent_letters = alphabet("opponent", opponent_letters, shapes.len())?;
+        let own_letters = alphabet("own", own_letters, shapes.len())?;
+        let result_letters = alphabet("result", result_letters, 3)?;
+        let mut result_targets = [RpsTarget(0); 3];
+        for (result_target, letter) in result_targets.iter_mut().zip(result_letters) {
+            *result_target = match own_letters.iter().position(|own| *own == letter) {
+                Some(index) => RpsTarget(index),
+                None => return Err(RpsRuleSetError::UnknownResultLetter(letter)),
+            };
+        }
+
+        let shape_type = |name: &str| match shapes.iter().position(|shape| shape.name == name) {
+            Some(index) => Ok(RpsType(index)),
+            None => Err(RpsRuleSetError::UnknownShape(name.to_string())),
+        };
+        let mut beats_set: HashSet<(RpsType, RpsType)> = HashSet::new();
+        for (winner, loser) in beats.iter() {
+            let (winner_type, loser_type) = (shape_type(winner)?, shape_type(loser)?);
+            if winner_type == loser_type {
+                return Err(RpsRuleSetError::BeatsItself(winner.to_string()));
+            }
+            if beats_set.contains(&(loser_type, winner_type)) {
+                return Err(RpsRuleSetError::BeatEachOther(
+                    winner.to_string(),
+                    loser.to_string(),
+                ));
+            }
+            beats_set.insert((winner_type, loser_type));
+        }
+
+        Ok(RpsRuleSet {
+            shapes,
+            beats: beats_set,
+            opponent_letters,
+            own_letters,
+            result_targets,
+        })
+    }
+
+    pub fn rock_paper_scissors() -> RpsRuleSet {
+        Self::new(
+            shapes(&[("rock", 1), ("paper", 2), ("scissors", 3)]),
+            &[
+                ("rock", "scissors"),
+                ("paper", "rock"),
+                ("scissors", "paper"),
+            ],
+            "ABC",
+            "XYZ",
+            "XYZ",
+        )
+        .unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> RpsRuleSet {
+        Self::new(
+            shapes(&[
+                ("rock", 1),
+                ("paper", 2),
+                ("scissors", 3),
+                ("lizard", 4),
+                ("spock", 5),
+            ]),
+            &[
+                ("rock", "scissors"),
+                ("rock", "lizard"),
+                ("paper", "rock"),
+                ("paper", "spock"),
+                ("scissors", "paper"),
+                ("scissors", "lizard"),
+                ("lizard", "paper"),
+                ("lizard", "spock"),
+                ("spock", "rock"),
+                ("spock", "scissors"),
+            ],
+            "ABCDE",
+            "VWXYZ",
+            "XYZ",
+        )
+        .unwrap()
+    }
+
+    /// Builds a cyclic game of an odd number of shapes from 3 to 13, in which every shape beats the
+    /// half of the other shapes that precede it, counting around. The shapes are scored 1, 2, ...
+    /// in order, the opponent letters start at `A`, and the own letters end at `Z`, the last three
+    /// of which also stand for a loss, a draw and a win.
+    pub fn cyclic(shape_count: usize) -> Result<RpsRuleSet, RpsRuleSetError> {
+        if shape_count.is_multiple_of(2) || !(3..=13).contains(&shape_count) {
+            return Err(RpsRuleSetError::CyclicShapeCount(shape_count));
+        }
+
+        let names = (0..shape_count)
+            .map(|index| format!("shape {}", index + 1))
+            .collect::<Vec<String>>();
+        let beats = (0..shape_count)
+            .flat_map(|winner| {
+                (1..=(shape_count / 2))
+                    .map(move |offset| (winner, (winner + shape_count - offset) % shape_count))
+            })
+            .map(|(winner, loser)| (names[winner].as_str(), names[loser].as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        let opponent_letters = ('A'..='Z').take(shape_count).collect::<String>();
+        let own_letters = ('A'..='Z').skip(26 - shape_count).collect::<String>();
+        let result_letters = ('X'..='Z').collect::<String>();
+        let shapes = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| RpsShape {
+                name: name.clone(),
+                score: index as i32 + 1,
+            })
+            .collect();
+
+        Self::new(
+            shapes,
+            &beats,
+            &opponent_letters,
+            &own_letters,
+            &result_letters,
+        )
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = RpsType> {
+        (0..self.shapes.len()).map(RpsType)
+    }
+
+    pub fn shape(&self, rps_type: RpsType) -> &RpsShape {
+        &self.shapes[rps_type.0]
+    }
+
+    pub fn result(&self, opponent_choice: RpsType, own_choice: RpsType) -> RpsResult {
+        if self.beats.contains(&(own_choice, opponent_choice)) {
+            RpsResult::Win
+        } else if self.beats.contains(&(opponent_choice, own_choice)) {
+            RpsResult::Loss
+        } else {
+            RpsResult::Draw
+        }
+    }
+
+    /// The shape that a target stands for, when targets are read as shapes.
+    pub fn target_as_type(&self, target: RpsTarget) -> RpsType {
+        RpsType(target.0)
+    }
+
+    /// The result that a target stands for, when targets are read as results, if any.
+    pub fn target_as_result(&self, target: RpsTarget) -> Option<RpsResult> {
+        [RpsResult::Loss, RpsResult::Draw, RpsResult::Win]
+            .into_iter()
+            .zip(self.result_targets)
+            .find(|(_, result_target)| *result_target == target)
+            .map(|(result, _)| result)
+    }
+
+    pub fn own_letter(&self, target: RpsTarget) -> char {
+        self.own_letters[target.0]
+    }
+
+    pub fn parse_strategy(&self, s: &str) -> Result<RpsStrategy, ParseRpsStrategyError> {
+        RpsStrategy::parse(s, &self.opponent_letters, &self.own_letters)
+    }
+
+    pub fn parse_strategies(
+        &self,
+        s: &str,
+    ) -> Result<ByLines<RpsStrategy>, ParseByLinesError<ParseRpsStrategyError>> {
+        ByLines::parse_with(s, |line| self.parse_strategy(line))
+    }
+}
+
+fn shapes(shapes: &[(&str, i32)]) -> Vec<RpsShape> {
+    shapes
+        .iter()
+        .map(|(name, score)| RpsShape {
+            name: name.to_string(),
+            score: *score,
+        })
+        .collect()
+}
+
+fn alphabet(
+    column: &'static str,
+    letters: &str,
+    expected: usize,
+) -> Result<Vec<char>, RpsRuleSetError> {
+    let letters = letters.chars().collect::<Vec<char>>();
+    if letters.len() != expected {
+        return Err(RpsRuleSetError::AlphabetSize {
+            column,
+            expected,
+            actual: letters.len(),
+        });
+    }
+    for (index, letter) in letters.iter().enumerate() {
+        if letters[..index].contains(letter) {
+            return Err(RpsRuleSetError::DuplicateLetter {
+                column,
+                letter: *letter,
+            });
+        }
+    }
+    Ok(letters)
+}
@@ -2,7 +2,7 @@ use std::iter::Sum;
 
 use super::{
     rps_match::{RpsMatch, RpsResult},
-    strategy::RpsType,
+    rule_set::RpsShape,
 };
 
 pub trait Scorable<T>
@@ -12,13 +12,9 @@ where
     fn score(&self) -> T;
 }
 
-impl Scorable<i32> for RpsType {
+impl Scorable<i32> for RpsShape {
     fn score(&self) -> i32 {
-        match &self {
-            Self::Rock => 1,
-            Self::Paper => 2,
-            Self::Scissors => 3,
-        }
+        self.score
     }
 }
 
@@ -32,7 +28,7 @@ impl Scorable<i32> for RpsResult {
     }
 }
 
-impl Scorable<i32> for RpsMatch {
+impl Scorable<i32> for RpsMatch<'_> {
     fn score(&self) -> i32 {
         self.own_choice.score() + self.result.score()
     }
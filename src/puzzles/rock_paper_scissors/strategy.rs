@@ -1,7 +1,6 @@
 use std::{
     error::Error,
     fmt::{self, Debug, Display, Formatter},
-    str::FromStr,
 };
 
 use crate::validation::{
@@ -12,97 +11,14 @@ use crate::validation::{
 #[cfg(feature = "wasm")]
 use serde::Serialize;
 
-#[derive(Debug)]
-pub enum ParseRpsTypeError {
-    Format { format: FormatError },
-}
-
-impl Display for ParseRpsTypeError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Format { format } => write!(f, "{}", format),
-        }
-    }
-}
-
-impl Error for ParseRpsTypeError {}
-
+/// A shape of a rule set, as an index into its shapes.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-pub enum RpsType {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl FormatDescription for RpsType {
-    const FORMAT_DESCRIPTION: &'static str = "A|B|C";
-}
-
-impl FromStr for RpsType {
-    type Err = ParseRpsTypeError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" => Ok(Self::Rock),
-            "B" => Ok(Self::Paper),
-            "C" => Ok(Self::Scissors),
-            _ => Err(Self::Err::Format {
-                format: FormatError::from_actual::<RpsType>(s),
-            }),
-        }
-    }
-}
-
-#[derive(Debug)]
-pub enum ParseRpsTargetError {
-    Format { format: FormatError },
-}
-
-impl Display for ParseRpsTargetError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Format { format } => write!(f, "{}", format),
-        }
-    }
-}
-
-impl Error for ParseRpsTargetError {}
-
-#[derive(PartialEq, Clone, Copy)]
-pub enum RpsTarget {
-    X,
-    Y,
-    Z,
-}
-
-impl FormatDescription for RpsTarget {
-    const FORMAT_DESCRIPTION: &'static str = "X|Y|Z";
-}
-
-impl FromStr for RpsTarget {
-    type Err = ParseRpsTargetError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "X" => Ok(Self::X),
-            "Y" => Ok(Self::Y),
-            "Z" => Ok(Self::Z),
-            _ => Err(Self::Err::Format {
-                format: FormatError::from_actual::<RpsTarget>(s),
-            }),
-        }
-    }
-}
+pub struct RpsType(pub usize);
 
-impl From<RpsTarget> for RpsType {
-    fn from(value: RpsTarget) -> Self {
-        match value {
-            RpsTarget::X => RpsType::Rock,
-            RpsTarget::Y => RpsType::Paper,
-            RpsTarget::Z => RpsType::Scissors,
-        }
-    }
-}
+/// A letter in the second column of a strategy guide, as an index into the own alphabet of a rule
+/// set.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RpsTarget(pub usize);
 
 #[derive(Debug)]
 #[cfg_attr(feature = "wasm", derive(Serialize))]
@@ -148,40 +64,7 @@ impl Display for ParseRpsStrategyError {
 
 impl Error for ParseRpsStrategyError {}
 
-impl From<ParseRpsTypeError> for ParseRpsStrategyError {
-    fn from(value: ParseRpsTypeError) -> Self {
-        match value {
-            ParseRpsTypeError::Format {
-                format: opponent_choice,
-            } => Self::OpponentChoice { opponent_choice },
-        }
-    }
-}
-
-impl From<ParseRpsTargetError> for ParseRpsStrategyError {
-    fn from(value: ParseRpsTargetError) -> Self {
-        match value {
-            ParseRpsTargetError::Format { format: target } => Self::Target { target },
-        }
-    }
-}
-
-impl From<(ParseRpsTypeError, ParseRpsTargetError)> for ParseRpsStrategyError {
-    fn from(value: (ParseRpsTypeError, ParseRpsTargetError)) -> Self {
-        match value {
-            (
-                ParseRpsTypeError::Format {
-                    format: opponent_choice,
-                },
-                ParseRpsTargetError::Format { format: target },
-            ) => Self::Both {
-                opponent_choice,
-                target,
-            },
-        }
-    }
-}
-
+#[derive(Debug, Clone, Copy)]
 pub struct RpsStrategy {
     pub opponent_choice: RpsType,
     pub target: RpsTarget,
@@ -191,41 +74,65 @@ impl FormatDescription for RpsStrategy {
     const FORMAT_DESCRIPTION: &'static str = "<opponent choice> <target>";
 }
 
-impl FromStr for RpsStrategy {
-    type Err = ParseRpsStrategyError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl RpsStrategy {
+    /// Parses a line of a strategy guide, with letters from the given alphabets.
+    pub fn parse(
+        s: &str,
+        opponent_letters: &[char],
+        own_letters: &[char],
+    ) -> Result<RpsStrategy, ParseRpsStrategyError> {
         let mut tokens = s.split_whitespace();
 
         let opponent_choice = match tokens.next() {
-            Some(opponent_choice) => opponent_choice.parse::<RpsType>(),
+            Some(opponent_choice) => parse_letter(opponent_choice, opponent_letters),
             None => {
                 return Err(ParseRpsStrategyError::Empty { required: True });
             }
         };
 
         let target = match tokens.next() {
-            Some(target) => target.parse::<RpsTarget>(),
+            Some(target) => parse_letter(target, own_letters),
             None => {
-                return Err(Self::Err::Format {
+                return Err(ParseRpsStrategyError::Format {
                     format: FormatError::from_actual::<RpsStrategy>(s),
                 });
             }
         };
 
         match tokens.next() {
-            Some(_) => Err(Self::Err::Format {
+            Some(_) => Err(ParseRpsStrategyError::Format {
                 format: FormatError::from_actual::<RpsStrategy>(s),
             }),
             None => match (opponent_choice, target) {
                 (Ok(opponent_choice), Ok(target)) => Ok(RpsStrategy {
+                    opponent_choice: RpsType(opponent_choice),
+                    target: RpsTarget(target),
+                }),
+                (Ok(_), Err(target)) => Err(ParseRpsStrategyError::Target { target }),
+                (Err(opponent_choice), Ok(_)) => {
+                    Err(ParseRpsStrategyError::OpponentChoice { opponent_choice })
+                }
+                (Err(opponent_choice), Err(target)) => Err(ParseRpsStrategyError::Both {
                     opponent_choice,
                     target,
                 }),
-                (Ok(_), Err(target)) => Err(target.into()),
-                (Err(opponent_choice), Ok(_)) => Err(opponent_choice.into()),
-                (Err(opponent_choice), Err(target)) => Err((opponent_choice, target).into()),
             },
         }
     }
 }
+
+fn parse_letter(s: &str, letters: &[char]) -> Result<usize, FormatError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => letters.iter().position(|letter| *letter == c),
+        _ => None,
+    }
+    .ok_or_else(|| FormatError {
+        format_description: letters
+            .iter()
+            .map(|letter| letter.to_string())
+            .collect::<Vec<String>>()
+            .join("|"),
+        actual: s.to_string(),
+    })
+}
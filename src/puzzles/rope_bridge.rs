@@ -1,11 +1,21 @@
+mod follow_rule;
 mod rope_motion;
 
-use self::rope_motion::{Direction, RopeMotion, RopePosition, RopePositionCollector};
+use std::{fmt::Write, num::NonZeroUsize};
+
+use self::{
+    follow_rule::FollowRule,
+    rope_motion::{Rope, RopeMotion},
+};
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
     parse::{error::ParseContentsError, lines::ByLines},
 };
-use std::collections::HashSet;
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
@@ -13,28 +23,96 @@ use wasm_bindgen::prelude::*;
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn rope_bridge(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let ByLines(motions) = input.file_contents.parse::<ByLines<RopeMotion>>()?;
-    let directions = motions
-        .into_iter()
-        .flat_map(|motion| (0..motion.count).map(move |_| motion.direction))
-        .collect::<Vec<Direction>>();
-    let tail_positions = match input.puzzle_part {
-        PuzzlePart::Part1 => {
-            let collector: RopePositionCollector<std::vec::IntoIter<Direction>, 2> =
-                RopePositionCollector::new(directions.into_iter());
-            let tail_positions: HashSet<RopePosition> = collector.into();
-            tail_positions
-        }
-        PuzzlePart::Part2 => {
-            let collector: RopePositionCollector<std::vec::IntoIter<Direction>, 10> =
-                RopePositionCollector::new(directions.into_iter());
-            let tail_positions: HashSet<RopePosition> = collector.into();
-            tail_positions
-        }
+    let knot_count = match input.puzzle_part {
+        PuzzlePart::Part1 => 2,
+        PuzzlePart::Part2 => 10,
     };
-    let count = tail_positions.len();
+    let rope = simulate(
+        &motions,
+        NonZeroUsize::new(knot_count).unwrap(),
+        FollowRule::default(),
+    );
+    let count = rope.tail_trail().len();
     Ok(count.to_string())
 }
 
+fn simulate(motions: &[RopeMotion], knot_count: NonZeroUsize, follow_rule: FollowRule) -> Rope {
+    let mut rope = Rope::new(knot_count, follow_rule);
+    for motion in motions {
+        rope.perform_motion(*motion);
+    }
+    rope
+}
+
+/// Reports on a rope of the number of knots given as first argument. Knots follow each other by the
+/// rule given as second argument: `chebyshev` (the default) or `manhattan` distance, with a slack
+/// of 1 or the one given after a colon, as in `manhattan:2`. The modes are:
+/// - `trace <knots> [rule]`: the rope drawn like in the puzzle statement, at the start and after
+///   every motion.
+/// - `trails <knots> [rule]`: the number of positions visited by every knot, and the positions
+///   visited by the tail drawn.
+pub fn rope_bridge_report(input: ReportInput) -> Result<String, ReportError> {
+    let mut output = String::new();
+    match input.mode.as_str() {
+        "trace" => {
+            let (motions, knot_count, follow_rule) = rope_arguments(&input)?;
+            let bounds = simulate(&motions, knot_count, follow_rule).bounds();
+            let mut rope = Rope::new(knot_count, follow_rule);
+            writeln!(output, "== Initial State ==\n\n{}", rope.draw(bounds)).unwrap();
+            for motion in motions {
+                rope.perform_motion(motion);
+                writeln!(output, "== {} ==\n\n{}", motion, rope.draw(bounds)).unwrap();
+            }
+        }
+        "trails" => {
+            let (motions, knot_count, follow_rule) = rope_arguments(&input)?;
+            let final_rope = simulate(&motions, knot_count, follow_rule);
+            for (index, trail) in final_rope.trails().iter().enumerate() {
+                writeln!(
+                    output,
+                    "{}: {} positions",
+                    final_rope.label(index),
+                    trail.len()
+                )
+                .unwrap();
+            }
+            let tail = knot_count.get() - 1;
+            write!(
+                output,
+                "\n{}",
+                final_rope.draw_trail(tail, final_rope.bounds())
+            )
+            .unwrap();
+        }
+        _ => return Err(input.unknown_mode(&["trace", "trails"])),
+    }
+    Ok(output)
+}
+
+fn rope_arguments(
+    input: &ReportInput,
+) -> Result<(Vec<RopeMotion>, NonZeroUsize, FollowRule), ReportError> {
+    let ByLines(motions) = input.file_contents.parse::<ByLines<RopeMotion>>()?;
+    let knot_count = input.required_argument::<NonZeroUsize>(0, "knot count")?;
+    let follow_rule = input
+        .argument::<FollowRule>(1, "follow rule")?
+        .unwrap_or_default();
+    Ok((motions, knot_count, follow_rule))
+}
+
+/// Generates `size` random motions of the head of the rope.
+pub fn rope_bridge_generate(random: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                random.choose(&["U", "D", "L", "R"]),
+                random.range(1..20)
+            )
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +171,103 @@ U 20
         assert_eq!("36", output);
         Ok(())
     }
+
+    #[test]
+    fn trace_should_draw_rope_after_every_motion() -> Result<(), Box<dyn Error>> {
+        let output = rope_bridge_report(ReportInput::new("trace", &["10"], "R 4\nU 4\n"))?;
+
+        assert_eq!(
+            "\
+== Initial State ==
+
+.....
+.....
+.....
+.....
+H....
+
+== R 4 ==
+
+.....
+.....
+.....
+.....
+4321H
+
+== U 4 ==
+
+....H
+....1
+..432
+.5...
+6....
+
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn trails_should_count_positions_of_every_knot() -> Result<(), Box<dyn Error>> {
+        let output = rope_bridge_report(ReportInput::new("trails", &["2"], INPUT_TEXT_1))?;
+
+        assert_eq!(
+            "\
+H: 21 positions
+T: 13 positions
+
+..##..
+...##.
+.####.
+....#.
+s###..
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn trails_should_follow_diagonal_moves_and_other_rules() -> Result<(), Box<dyn Error>> {
+        let input = "UR 3\nDR 2\nL 1\n";
+        let chebyshev = rope_bridge_report(ReportInput::new("trails", &["2"], input))?;
+        let manhattan = rope_bridge_report(ReportInput::new("trails", &["2", "manhattan"], input))?;
+
+        assert_eq!(
+            "\
+H: 7 positions
+T: 5 positions
+
+......
+..##..
+.#..#.
+s.....
+",
+            chebyshev
+        );
+        assert_eq!(
+            "\
+H: 7 positions
+T: 6 positions
+
+...#..
+..#.#.
+.#...#
+s.....
+",
+            manhattan
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_mode_should_be_rejected_before_arguments() {
+        let error = rope_bridge_report(ReportInput::new("bogus", &[], INPUT_TEXT_1)).unwrap_err();
+
+        assert_eq!(
+            "unknown report mode 'bogus' (expected one of trace, trails)",
+            error.to_string()
+        );
+    }
 }
@@ -0,0 +1,168 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
+
+use super::rope_motion::RopePosition;
+
+pub enum ParseFollowRuleError {
+    UnknownTension(String),
+    InvalidSlack(String),
+}
+
+impl Display for ParseFollowRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownTension(value) => write!(
+                f,
+                "tension '{}' is not one of chebyshev or manhattan",
+                value
+            ),
+            Self::InvalidSlack(value) => write!(f, "invalid slack '{}'", value),
+        }
+    }
+}
+
+impl Debug for ParseFollowRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for ParseFollowRuleError {}
+
+/// How the distance between two knots is measured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tension {
+    /// Knots touching diagonally are as close as knots touching side by side.
+    Chebyshev,
+    /// Knots touching diagonally are as far apart as knots two steps away in a straight line.
+    Manhattan,
+}
+
+impl Tension {
+    fn distance(&self, from: RopePosition, to: RopePosition) -> u32 {
+        let (diff_x, diff_y) = (from.x.abs_diff(to.x), from.y.abs_diff(to.y));
+        match self {
+            Self::Chebyshev => diff_x.max(diff_y),
+            Self::Manhattan => diff_x + diff_y,
+        }
+    }
+}
+
+/// When and how a knot follows the knot ahead of it: once the distance between them is more than
+/// the slack, the knot takes a step towards the other one, diagonally if they are neither in the
+/// same row nor in the same column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FollowRule {
+    pub tension: Tension,
+    pub slack: u32,
+}
+
+/// The rule of the puzzle, where knots must always be touching.
+impl Default for FollowRule {
+    fn default() -> Self {
+        FollowRule {
+            tension: Tension::Chebyshev,
+            slack: 1,
+        }
+    }
+}
+
+impl FromStr for FollowRule {
+    type Err = ParseFollowRuleError;
+
+    /// Parses a rule such as `manhattan` or `chebyshev:3`, with a slack of 1 if none is given.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tension, slack) = match s.split_once(':') {
+            Some((tension, slack)) => (
+                tension,
+                slack
+                    .parse::<u32>()
+                    .map_err(|_| Self::Err::InvalidSlack(slack.to_string()))?,
+            ),
+            None => (s, 1),
+        };
+        let tension = match tension {
+            "chebyshev" => Tension::Chebyshev,
+            "manhattan" => Tension::Manhattan,
+            _ => return Err(Self::Err::UnknownTension(tension.to_string())),
+        };
+        Ok(FollowRule { tension, slack })
+    }
+}
+
+impl FollowRule {
+    /// Returns where a knot goes after the knot ahead of it has moved. As knots move at most one
+    /// step in each direction at a time, a single step is always enough to be within the slack
+    /// again.
+    pub fn follow(&self, knot: RopePosition, leader: RopePosition) -> RopePosition {
+        if self.tension.distance(knot, leader) <= self.slack {
+            return knot;
+        }
+        RopePosition {
+            x: knot.x + (leader.x - knot.x).signum(),
+            y: knot.y + (leader.y - knot.y).signum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn default_rule_should_keep_knots_touching() {
+        let rule = FollowRule::default();
+        let knot = RopePosition { x: 0, y: 0 };
+
+        assert_eq!(knot, rule.follow(knot, RopePosition { x: 1, y: 1 }));
+        assert_eq!(
+            RopePosition { x: 1, y: 0 },
+            rule.follow(knot, RopePosition { x: 2, y: 0 })
+        );
+        assert_eq!(
+            RopePosition { x: 1, y: 1 },
+            rule.follow(knot, RopePosition { x: 2, y: 1 })
+        );
+    }
+
+    #[test]
+    fn rules_should_measure_distance_with_their_tension() -> Result<(), Box<dyn Error>> {
+        let knot = RopePosition { x: 0, y: 0 };
+        let leader = RopePosition { x: 2, y: 1 };
+
+        assert_eq!(
+            knot,
+            "chebyshev:2".parse::<FollowRule>()?.follow(knot, leader)
+        );
+        assert_eq!(
+            RopePosition { x: 1, y: 1 },
+            "manhattan:2".parse::<FollowRule>()?.follow(knot, leader)
+        );
+        assert_eq!(
+            RopePosition { x: 1, y: 1 },
+            "manhattan"
+                .parse::<FollowRule>()?
+                .follow(knot, RopePosition { x: 1, y: 1 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_rules_should_not_parse() {
+        assert_eq!(
+            "tension 'euclid' is not one of chebyshev or manhattan",
+            "euclid".parse::<FollowRule>().unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid slack '-1'",
+            "chebyshev:-1"
+                .parse::<FollowRule>()
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
@@ -2,9 +2,12 @@ use std::{
     collections::HashSet,
     error::Error,
     fmt::{self, Debug, Display, Formatter},
+    num::NonZeroUsize,
     str::FromStr,
 };
 
+use super::follow_rule::FollowRule;
+
 pub enum ParseRopeMotionError {
     InvalidFormat,
     InvalidDirection(String),
@@ -35,6 +38,10 @@ pub enum Direction {
     Right,
     Down,
     Left,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
 impl Direction {
@@ -44,6 +51,10 @@ impl Direction {
             Self::Right => RopePosition { x: 1, y: 0 },
             Self::Down => RopePosition { x: 0, y: -1 },
             Self::Left => RopePosition { x: -1, y: 0 },
+            Self::UpLeft => RopePosition { x: -1, y: 1 },
+            Self::UpRight => RopePosition { x: 1, y: 1 },
+            Self::DownLeft => RopePosition { x: -1, y: -1 },
+            Self::DownRight => RopePosition { x: 1, y: -1 },
         }
     }
 }
@@ -54,6 +65,27 @@ pub struct RopeMotion {
     pub count: usize,
 }
 
+impl Display for Direction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Up => write!(f, "U"),
+            Self::Right => write!(f, "R"),
+            Self::Down => write!(f, "D"),
+            Self::Left => write!(f, "L"),
+            Self::UpLeft => write!(f, "UL"),
+            Self::UpRight => write!(f, "UR"),
+            Self::DownLeft => write!(f, "DL"),
+            Self::DownRight => write!(f, "DR"),
+        }
+    }
+}
+
+impl Display for RopeMotion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.direction, self.count)
+    }
+}
+
 impl FromStr for RopeMotion {
     type Err = ParseRopeMotionError;
 
@@ -69,6 +101,10 @@ impl FromStr for RopeMotion {
             "R" => Ok(Direction::Right),
             "D" => Ok(Direction::Down),
             "L" => Ok(Direction::Left),
+            "UL" => Ok(Direction::UpLeft),
+            "UR" => Ok(Direction::UpRight),
+            "DL" => Ok(Direction::DownLeft),
+            "DR" => Ok(Direction::DownRight),
             _ => Err(ParseRopeMotionError::InvalidDirection(
                 direction_token.to_string(),
             )),
@@ -89,89 +125,115 @@ pub struct RopePosition {
     pub y: i32,
 }
 
-impl RopePosition {
-    fn move_to(&mut self, other: RopePosition) -> () {
-        let diff_x = other.x - self.x;
-        let diff_y = other.y - self.y;
-        if (diff_x == 2 || diff_x == -2) && (diff_y == 2 || diff_y == -2) {
-            self.x += diff_x / 2;
-            self.y += diff_y / 2;
-        } else if diff_x == 2 || diff_x == -2 {
-            self.x += diff_x / 2;
-            self.y += diff_y;
-        } else if diff_y == 2 || diff_y == -2 {
-            self.y += diff_y / 2;
-            self.x += diff_x;
-        };
-    }
+/// A rope of any number of knots, with the positions that every knot has visited.
+pub struct Rope {
+    knots: Vec<RopePosition>,
+    trails: Vec<HashSet<RopePosition>>,
+    follow_rule: FollowRule,
 }
 
-pub struct RopePositionCollector<T, const N: usize>
-where
-    T: Iterator<Item = Direction>,
-{
-    source: T,
-    knots: [RopePosition; N],
-    tail_positions: HashSet<RopePosition>,
-}
+impl Rope {
+    /// Creates a rope of the given number of knots, all at the starting position, whose knots
+    /// follow each other by the given rule.
+    pub fn new(knot_count: NonZeroUsize, follow_rule: FollowRule) -> Rope {
+        let start = RopePosition { x: 0, y: 0 };
+        Rope {
+            knots: vec![start; knot_count.get()],
+            trails: vec![HashSet::from([start]); knot_count.get()],
+            follow_rule,
+        }
+    }
+
+    /// Returns the positions visited by every knot, starting with the head.
+    pub fn trails(&self) -> &[HashSet<RopePosition>] {
+        &self.trails
+    }
 
-impl<T, const N: usize> RopePositionCollector<T, N>
-where
-    T: Iterator<Item = Direction>,
-{
-    fn process_direction(&mut self, direction: Direction) -> () {
-        self.process_head_motion(direction);
-        self.process_tail_motion();
+    pub fn tail_trail(&self) -> &HashSet<RopePosition> {
+        self.trails.last().unwrap()
     }
 
-    fn process_head_motion(&mut self, direction: Direction) -> () {
+    /// Moves the head a step in the given direction, and lets the other knots follow.
+    pub fn step(&mut self, direction: Direction) {
         let relative = direction.relative_position();
         self.knots[0].x += relative.x;
         self.knots[0].y += relative.y;
+        for i in 1..self.knots.len() {
+            self.knots[i] = self.follow_rule.follow(self.knots[i], self.knots[i - 1]);
+        }
+        for (trail, knot) in self.trails.iter_mut().zip(self.knots.iter()) {
+            trail.insert(*knot);
+        }
     }
 
-    fn process_tail_motion(&mut self) -> () {
-        for i in 1..N {
-            self.knots[i].move_to(self.knots[i - 1]);
+    pub fn perform_motion(&mut self, motion: RopeMotion) {
+        for _ in 0..motion.count {
+            self.step(motion.direction);
         }
-        self.tail_positions.insert(self.knots[N - 1]);
     }
-}
 
-impl<T, const N: usize> RopePositionCollector<T, N>
-where
-    T: Iterator<Item = Direction>,
-{
-    pub fn new(iter: T) -> RopePositionCollector<T, N> {
-        RopePositionCollector {
-            source: iter,
-            knots: [RopePosition { x: 0, y: 0 }; N],
-            tail_positions: HashSet::new(),
+    /// Returns the character that stands for a knot in drawings: `H` for the head, and `T` for the
+    /// tail of a rope of two knots. Other knots are numbered from 1 to 9, and beyond that the tail
+    /// is `T` and the other knots are `*`.
+    pub fn label(&self, index: usize) -> char {
+        let tail = index == self.knots.len() - 1;
+        match index {
+            0 => 'H',
+            _ if tail && (index == 1 || index > 9) => 'T',
+            1..=9 => char::from_digit(index as u32, 10).unwrap(),
+            _ => '*',
         }
     }
-}
 
-impl<T, const N: usize> Iterator for RopePositionCollector<T, N>
-where
-    T: Iterator<Item = Direction>,
-{
-    type Item = ();
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(direction) = self.source.next() {
-            Some(self.process_direction(direction))
-        } else {
-            None
-        }
+    /// Draws the rope in the given area like the puzzle does, with up at the top. Knots closer to
+    /// the head cover the ones behind them, and `s` marks the start if no knot covers it.
+    pub fn draw(&self, (min, max): (RopePosition, RopePosition)) -> String {
+        draw_area(min, max, |position| {
+            match self.knots.iter().position(|knot| *knot == position) {
+                Some(index) => self.label(index),
+                None if position == (RopePosition { x: 0, y: 0 }) => 's',
+                None => '.',
+            }
+        })
+    }
+
+    /// Draws the positions visited by a knot as `#`, with `s` marking the start.
+    pub fn draw_trail(&self, index: usize, (min, max): (RopePosition, RopePosition)) -> String {
+        draw_area(min, max, |position| {
+            if position == (RopePosition { x: 0, y: 0 }) {
+                's'
+            } else if self.trails[index].contains(&position) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+    }
+
+    /// Returns the corners of the smallest area that contains all positions visited so far.
+    pub fn bounds(&self) -> (RopePosition, RopePosition) {
+        let positions = self.trails.iter().flatten();
+        let min = positions
+            .clone()
+            .fold(RopePosition { x: 0, y: 0 }, |min, p| RopePosition {
+                x: min.x.min(p.x),
+                y: min.y.min(p.y),
+            });
+        let max = positions.fold(RopePosition { x: 0, y: 0 }, |max, p| RopePosition {
+            x: max.x.max(p.x),
+            y: max.y.max(p.y),
+        });
+        (min, max)
     }
 }
 
-impl<T, const N: usize> From<RopePositionCollector<T, N>> for HashSet<RopePosition>
-where
-    T: Iterator<Item = Direction>,
-{
-    fn from(mut collector: RopePositionCollector<T, N>) -> Self {
-        while Some(()) == collector.next() {}
-        collector.tail_positions
+fn draw_area(min: RopePosition, max: RopePosition, cell: impl Fn(RopePosition) -> char) -> String {
+    let mut output = String::new();
+    for y in (min.y..=max.y).rev() {
+        for x in min.x..=max.x {
+            output.push(cell(RopePosition { x, y }));
+        }
+        output.push('\n');
     }
+    output
 }
@@ -1,33 +1,182 @@
 mod lib;
 
+use std::{fmt::Write, num::NonZeroUsize};
+
 use self::lib::Rucksack;
 use crate::{
-    common::vector_chunks::AsVectorChunks,
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
-    parse::{error::ParseContentsError, lines::ByLines},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
+    parse::{
+        error::ParseContentsError,
+        lines::{ByLines, ParseByLineError},
+    },
 };
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+/// The number of elves in a group. Part 2 always uses groups of this size, as in the puzzle;
+/// other sizes can only be looked at with the `groups` report.
+const GROUP_SIZE: usize = 3;
+
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn rucksack_reorganization(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let ByLines(rucksacks) = input.file_contents.parse::<ByLines<Rucksack>>()?;
     let answer = match input.puzzle_part {
         PuzzlePart::Part1 => rucksacks
-            .into_iter()
-            .map(|rucksack| lib::find_common_item(rucksack.compartments()).priority())
-            .sum::<i32>(),
-        PuzzlePart::Part2 => rucksacks
-            .into_iter()
-            .vector_chunks::<3>()
-            .map(|group| lib::find_common_item(group).priority())
-            .sum::<i32>(),
+            .iter()
+            .enumerate()
+            .map(|(line, rucksack)| {
+                lib::find_common_item(&rucksack.compartments())
+                    .map(|item| item.priority())
+                    .map_err(|error| ParseContentsError::new(ParseByLineError::new(line, error)))
+            })
+            .sum::<Result<i32, ParseContentsError>>()?,
+        PuzzlePart::Part2 => {
+            lib::badge_priorities(&rucksacks, GROUP_SIZE).map_err(ParseContentsError::new)?
+        }
     };
 
     Ok(answer.to_string())
 }
 
+/// Reports on the rucksacks. The modes are:
+/// - `compartments`: the items that the compartments of each rucksack share, and the items that
+///   are only in one of them.
+/// - `groups [size]`: the items that the rucksacks of each group (of 3 by default) have in common,
+///   pointing out groups without exactly one badge and trailing rucksacks that do not fill a group.
+pub fn rucksack_reorganization_report(input: ReportInput) -> Result<String, ReportError> {
+    match input.mode.as_str() {
+        "compartments" => {
+            let ByLines(rucksacks) = input.file_contents.parse::<ByLines<Rucksack>>()?;
+            Ok(compartments_report(&rucksacks))
+        }
+        "groups" => {
+            let ByLines(rucksacks) = input.file_contents.parse::<ByLines<Rucksack>>()?;
+            let group_size = input
+                .argument::<NonZeroUsize>(0, "group size")?
+                .map_or(GROUP_SIZE, NonZeroUsize::get);
+            Ok(groups_report(&rucksacks, group_size))
+        }
+        _ => Err(input.unknown_mode(&["compartments", "groups"])),
+    }
+}
+
+fn compartments_report(rucksacks: &[Rucksack]) -> String {
+    let mut output = String::new();
+    for (index, rucksack) in rucksacks.iter().enumerate() {
+        let [first, second] = rucksack.compartments();
+        let shared = first.intersection(second);
+        writeln!(
+            output,
+            "line {}: shared {} (priority {}); only in first {}; only in second {}",
+            index + 1,
+            shared,
+            shared.priorities(),
+            first.difference(second),
+            second.difference(first)
+        )
+        .unwrap();
+    }
+    output
+}
+
+fn groups_report(rucksacks: &[Rucksack], group_size: usize) -> String {
+    let mut output = String::new();
+    let mut malformed_count: usize = 0;
+    let group_count = rucksacks.len().div_ceil(group_size);
+
+    for (index, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_line = index * group_size + 1;
+        write!(
+            output,
+            "lines {}-{}: ",
+            first_line,
+            first_line + group.len() - 1
+        )
+        .unwrap();
+        if group.len() < group_size {
+            malformed_count += 1;
+            writeln!(
+                output,
+                "incomplete group of {} rucksack{}",
+                group.len(),
+                if group.len() == 1 { "" } else { "s" }
+            )
+            .unwrap();
+            continue;
+        }
+        let common_items = lib::common_items(group);
+        match common_items.len() {
+            0 => writeln!(output, "no common item").unwrap(),
+            1 => writeln!(output, "{}", common_items).unwrap(),
+            _ => writeln!(output, "several common items: {}", common_items).unwrap(),
+        }
+        if common_items.len() != 1 {
+            malformed_count += 1;
+        }
+    }
+
+    if malformed_count > 0 {
+        writeln!(
+            output,
+            "{} of {} groups are malformed",
+            malformed_count, group_count
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            output,
+            "sum of badge priorities: {}",
+            lib::badge_priorities(rucksacks, group_size).unwrap_or_default()
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Generates `size` groups of three rucksacks. Each rucksack has exactly one item type in both of
+/// its compartments, and each group has exactly one badge item type in common.
+pub fn rucksack_reorganization_generate(random: &mut Random, size: usize) -> String {
+    let mut items = ('a'..='z').chain('A'..='Z').collect::<Vec<char>>();
+    let mut output = String::new();
+
+    for _ in 0..size {
+        random.shuffle(&mut items);
+        let badge = items[0];
+        // Apart from the badge, every rucksack draws from its own pool of item types
+        for pool in items[1..].chunks(17) {
+            let shared = pool[0];
+            let (first_pool, second_pool) = pool[1..].split_at(8);
+            let compartment_size = random.range(2..17) as usize;
+            let mut first = (1..compartment_size)
+                .map(|_| *random.choose(first_pool))
+                .collect::<Vec<char>>();
+            let mut second = (1..compartment_size)
+                .map(|_| *random.choose(second_pool))
+                .collect::<Vec<char>>();
+            first.push(shared);
+            second.push(shared);
+            if random.chance(1, 2) {
+                first[0] = badge;
+            } else {
+                second[0] = badge;
+            }
+            random.shuffle(&mut first);
+            random.shuffle(&mut second);
+            output.extend(first);
+            output.extend(second);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
@@ -64,4 +213,64 @@ CrZsJsPPZsGzwwsLwLmpwMDw
         assert_eq!("70", output.to_string());
         Ok(())
     }
+
+    #[test]
+    fn compartments_should_list_shared_and_unique_items() -> Result<(), Box<dyn Error>> {
+        let input_text = INPUT_TEXT.lines().next().unwrap();
+        let output =
+            rucksack_reorganization_report(ReportInput::new("compartments", &[], input_text))?;
+
+        assert_eq!(
+            "line 1: shared p (priority 16); only in first g, r, t, v, w, J, W; \
+             only in second c, f, h, s, F, M\n",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn groups_should_list_common_items() -> Result<(), Box<dyn Error>> {
+        let output = rucksack_reorganization_report(ReportInput::new("groups", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+lines 1-3: r
+lines 4-6: Z
+sum of badge priorities: 70
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn groups_should_report_malformed_groups() -> Result<(), Box<dyn Error>> {
+        let output =
+            rucksack_reorganization_report(ReportInput::new("groups", &["4"], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+lines 1-4: no common item
+lines 5-6: incomplete group of 2 rucksacks
+2 of 2 groups are malformed
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn incomplete_group_should_be_an_error() {
+        let input_text = INPUT_TEXT.lines().take(4).collect::<Vec<&str>>().join("\n");
+        let error = rucksack_reorganization(PuzzleInput {
+            file_contents: input_text,
+            puzzle_part: PuzzlePart::Part2,
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            "group starting at line 4 has only 1 rucksack\n",
+            error.to_string()
+        );
+    }
 }
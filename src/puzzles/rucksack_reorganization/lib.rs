@@ -1,8 +1,7 @@
 use std::{
     error::Error,
-    fmt::{self, Display, Formatter},
+    fmt::{self, Debug, Display, Formatter},
     str::FromStr,
-    vec::IntoIter,
 };
 
 #[derive(Debug)]
@@ -28,6 +27,22 @@ pub enum RucksackItem {
 }
 
 impl RucksackItem {
+    fn parse(c: char) -> Option<RucksackItem> {
+        if c.is_ascii_alphabetic() {
+            Some(RucksackItem::Item(c))
+        } else {
+            None
+        }
+    }
+
+    fn from_priority(priority: i32) -> RucksackItem {
+        match priority {
+            1..=26 => RucksackItem::Item((b'a' + (priority - 1) as u8) as char),
+            27..=52 => RucksackItem::Item((b'A' + (priority - 27) as u8) as char),
+            _ => unreachable!("Item sets only contain priorities from 1 to 52."),
+        }
+    }
+
     pub fn priority(&self) -> i32 {
         let RucksackItem::Item(value) = self;
         if *value >= 'a' && *value <= 'z' {
@@ -40,42 +55,99 @@ impl RucksackItem {
     }
 }
 
-pub trait RucksackItemCollection {
-    fn as_collection(&self) -> Vec<RucksackItem>;
+/// A set of item types, with a bit per priority.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub fn all() -> ItemSet {
+        ItemSet(((1u64 << 52) - 1) << 1)
+    }
+
+    pub fn insert(&mut self, item: RucksackItem) {
+        self.0 |= 1 << item.priority();
+    }
+
+    pub fn union(&self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    pub fn intersection(&self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    pub fn difference(&self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & !other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the items in order of priority.
+    pub fn items(&self) -> impl Iterator<Item = RucksackItem> {
+        let bits = self.0;
+        (1..=52)
+            .filter(move |priority| bits & (1 << priority) != 0)
+            .map(RucksackItem::from_priority)
+    }
+
+    pub fn priorities(&self) -> i32 {
+        self.items().map(|item| item.priority()).sum()
+    }
+}
+
+impl FromIterator<RucksackItem> for ItemSet {
+    fn from_iter<T: IntoIterator<Item = RucksackItem>>(iter: T) -> Self {
+        let mut item_set = ItemSet::default();
+        for item in iter {
+            item_set.insert(item);
+        }
+        item_set
+    }
+}
+
+impl Display for ItemSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "none");
+        }
+        let items = self
+            .items()
+            .map(|RucksackItem::Item(value)| value.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", items.join(", "))
+    }
 }
 
-pub enum RucksackCompartment<'a> {
-    Compartment(&'a [RucksackItem]),
+pub trait RucksackItemCollection {
+    fn item_set(&self) -> ItemSet;
 }
 
-impl<'a> RucksackItemCollection for RucksackCompartment<'a> {
-    fn as_collection(&self) -> Vec<RucksackItem> {
-        let Self::Compartment(contents) = self;
-        let mut contents = contents.iter().map(|x| *x).collect::<Vec<RucksackItem>>();
-        contents.sort();
-        contents
+impl RucksackItemCollection for ItemSet {
+    fn item_set(&self) -> ItemSet {
+        *self
     }
 }
 
 pub struct Rucksack {
-    contents: Vec<RucksackItem>, // Here to take ownership of the data the slices refer to
-    first_compartment_size: usize,
+    first_compartment: ItemSet,
+    second_compartment: ItemSet,
 }
 
 impl RucksackItemCollection for Rucksack {
-    fn as_collection(&self) -> Vec<RucksackItem> {
-        let mut contents = self.contents.clone();
-        contents.sort();
-        contents
+    fn item_set(&self) -> ItemSet {
+        self.first_compartment.union(self.second_compartment)
     }
 }
 
 impl Rucksack {
-    pub fn compartments(&self) -> Vec<RucksackCompartment> {
-        vec![
-            RucksackCompartment::Compartment(&self.contents[0..self.first_compartment_size]),
-            RucksackCompartment::Compartment(&self.contents[self.first_compartment_size..]),
-        ]
+    pub fn compartments(&self) -> [ItemSet; 2] {
+        [self.first_compartment, self.second_compartment]
     }
 }
 
@@ -85,84 +157,143 @@ impl FromStr for Rucksack {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let contents = s
             .chars()
-            .map(|c| {
-                if (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') {
-                    Ok(RucksackItem::Item(c))
-                } else {
-                    Err(Self::Err::InvalidItem)
-                }
-            })
+            .map(|c| RucksackItem::parse(c).ok_or(Self::Err::InvalidItem))
             .collect::<Result<Vec<RucksackItem>, Self::Err>>()?;
         let count = contents.len();
         if count % 2 != 0 {
             return Err(Self::Err::UnevenRucksack);
         }
-        let first_compartment_size = count / 2;
+        let (first_compartment, second_compartment) = contents.split_at(count / 2);
         Ok(Self {
-            contents,
-            first_compartment_size,
+            first_compartment: first_compartment.iter().copied().collect(),
+            second_compartment: second_compartment.iter().copied().collect(),
         })
     }
 }
 
-pub struct VectorChunkIterator<'a, const N: usize, T, U>
-where
-    U: Iterator<Item = T>,
-{
-    pub iterator: &'a mut U,
+pub enum CommonItemError {
+    NoCommonItem,
+    SeveralCommonItems(ItemSet),
 }
 
-impl<'a, const N: usize, T, U> Iterator for VectorChunkIterator<'a, N, T, U>
-where
-    U: Iterator<Item = T>,
-{
-    type Item = Vec<T>;
+impl Display for CommonItemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCommonItem => write!(f, "no common item"),
+            Self::SeveralCommonItems(items) => {
+                write!(f, "several common items: {}", items)
+            }
+        }
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let result = self.iterator.by_ref().take(N).collect::<Vec<T>>();
-        if result.len() == N {
-            Some(result)
-        } else {
-            None
+impl Debug for CommonItemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for CommonItemError {}
+
+/// An error in a group of rucksacks, which starts at the given line numbered from 1.
+pub enum RucksackGroupError {
+    Incomplete {
+        first_line: usize,
+        size: usize,
+    },
+    CommonItem {
+        first_line: usize,
+        error: CommonItemError,
+    },
+}
+
+impl Display for RucksackGroupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Incomplete { first_line, size } => write!(
+                f,
+                "group starting at line {} has only {} rucksack{}",
+                first_line,
+                size,
+                if *size == 1 { "" } else { "s" }
+            ),
+            Self::CommonItem { first_line, error } => {
+                write!(f, "in group starting at line {}: {}", first_line, error)
+            }
         }
     }
 }
 
-pub fn find_common_item<T>(collections: Vec<T>) -> RucksackItem
+impl Debug for RucksackGroupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        (self as &dyn Display).fmt(f)
+    }
+}
+
+impl Error for RucksackGroupError {}
+
+/// Returns the item types that are in all of the collections.
+pub fn common_items<T>(collections: &[T]) -> ItemSet
 where
     T: RucksackItemCollection,
 {
-    let mut iters = collections
+    collections
         .iter()
-        .map(|collection| collection.as_collection().into_iter())
-        .collect::<Vec<IntoIter<RucksackItem>>>();
-    let mut current = iters
-        .iter_mut()
-        .map(|iter| iter.next())
-        .collect::<Vec<Option<RucksackItem>>>();
-
-    while current.iter().all(|value| value.is_some()) {
-        let current_values = current
-            .iter()
-            .filter_map(|value| value.as_ref())
-            .collect::<Vec<&RucksackItem>>();
-        let first_value = current_values[0];
-        if current_values.iter().all(|value| *value == first_value) {
-            return *first_value;
-        }
+        .fold(ItemSet::all(), |common_items, collection| {
+            common_items.intersection(collection.item_set())
+        })
+}
 
-        if let Some(min_value) = current_values.iter().min() {
-            let minimal_indices = current_values
-                .iter()
-                .enumerate()
-                .filter(|(_, x)| *x == min_value)
-                .map(|(index, _)| index)
-                .collect::<Vec<usize>>();
-            for index in minimal_indices {
-                current[index] = iters[index].next();
-            }
-        }
+/// Returns the item type that is in all of the collections, if there is exactly one.
+pub fn find_common_item<T>(collections: &[T]) -> Result<RucksackItem, CommonItemError>
+where
+    T: RucksackItemCollection,
+{
+    let common_items = common_items(collections);
+    match common_items.len() {
+        0 => Err(CommonItemError::NoCommonItem),
+        1 => Ok(common_items.items().next().unwrap()),
+        _ => Err(CommonItemError::SeveralCommonItems(common_items)),
     }
+}
+
+/// Sums the priorities of the badges of consecutive groups of rucksacks of the given size.
+pub fn badge_priorities(
+    rucksacks: &[Rucksack],
+    group_size: usize,
+) -> Result<i32, RucksackGroupError> {
+    rucksacks
+        .chunks(group_size)
+        .enumerate()
+        .map(|(index, group)| {
+            let first_line = index * group_size + 1;
+            if group.len() < group_size {
+                return Err(RucksackGroupError::Incomplete {
+                    first_line,
+                    size: group.len(),
+                });
+            }
+            find_common_item(group)
+                .map(|item| item.priority())
+                .map_err(|error| RucksackGroupError::CommonItem { first_line, error })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
 
-    unreachable!("Input data should always have a common item.");
+    #[test]
+    fn set_operations_should_combine_compartments() -> Result<(), Box<dyn Error>> {
+        let rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".parse::<Rucksack>()?;
+        let [first, second] = rucksack.compartments();
+
+        assert_eq!("p", first.intersection(second).to_string());
+        assert_eq!("g, r, t, v, w, J, W", first.difference(second).to_string());
+        assert_eq!(16, first.intersection(second).priorities());
+        assert_eq!(rucksack.item_set(), first.union(second));
+        Ok(())
+    }
 }
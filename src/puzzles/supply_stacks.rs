@@ -1,15 +1,22 @@
 mod crate_cell;
 mod crate_stacks;
 mod move_instruction;
+mod procedure;
+
+use std::{fmt::Write, num::NonZeroUsize};
 
 use self::{
-    crate_cell::CrateCell,
     crate_stacks::{CrateStacks, MoveMode},
-    move_instruction::MoveInstruction,
+    procedure::Procedure,
 };
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
-    parse::{error::ParseContentsError, grid::Grid, lines::ByLines, section_pair::SectionPair},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
+    parse::error::ParseContentsError,
 };
 
 #[cfg(feature = "wasm")]
@@ -17,18 +24,83 @@ use wasm_bindgen::prelude::*;
 
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn supply_stacks(input: PuzzleInput) -> Result<String, ParseContentsError> {
-    let SectionPair(grid, ByLines(instructions)) = input
-        .file_contents
-        .parse::<SectionPair<Grid<3, 1, CrateCell>, ByLines<MoveInstruction>>>()?;
-    let mut crate_stacks: CrateStacks = grid.into();
-    let move_mode = match input.puzzle_part {
+    let mut procedure = Procedure::parse(&input.file_contents)?;
+    procedure.perform_instructions(move_mode(input.puzzle_part), |_, _, _| {})?;
+
+    Ok(procedure.crate_stacks.get_stack_tops())
+}
+
+fn move_mode(puzzle_part: PuzzlePart) -> MoveMode {
+    match puzzle_part {
         PuzzlePart::Part1 => MoveMode::OneByOne,
         PuzzlePart::Part2 => MoveMode::AllAtOnce,
-    };
+    }
+}
 
-    crate_stacks.perform_instructions(&instructions, move_mode);
+/// Reports on the rearrangement procedure. The `trace <part> [every]` mode draws the stacks before
+/// the first instruction, after every instruction (or every given number of instructions), and
+/// after the last one.
+pub fn supply_stacks_report(input: ReportInput) -> Result<String, ReportError> {
+    match input.mode.as_str() {
+        "trace" => {
+            let puzzle_part = input.required_argument::<PuzzlePart>(0, "part")?;
+            let every = input
+                .argument::<NonZeroUsize>(1, "every")?
+                .map_or(1, NonZeroUsize::get);
+            let mut procedure = Procedure::parse(&input.file_contents)?;
+            let instruction_count = procedure.instruction_count();
 
-    Ok(crate_stacks.get_stack_tops())
+            let mut output = String::new();
+            writeln!(output, "before the first move:").unwrap();
+            writeln!(output, "{}", procedure.crate_stacks).unwrap();
+            procedure.perform_instructions(
+                move_mode(puzzle_part),
+                |performed, instruction, crate_stacks| {
+                    if performed.is_multiple_of(every) || performed == instruction_count {
+                        writeln!(output, "\nafter move {} ({}):", performed, instruction).unwrap();
+                        writeln!(output, "{}", crate_stacks).unwrap();
+                    }
+                },
+            )?;
+            Ok(output)
+        }
+        _ => Err(input.unknown_mode(&["trace"])),
+    }
+}
+
+/// Generates a drawing of three to nine crate stacks, followed by `size` move instructions that never
+/// move more crates than a stack holds.
+pub fn supply_stacks_generate(random: &mut Random, size: usize) -> String {
+    let stack_count = random.range(3..10) as usize;
+    let mut stacks = (0..stack_count)
+        .map(|_| {
+            (0..random.range(0..9))
+                .map(|_| char::from(b'A' + random.below(26) as u8).to_string())
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<Vec<String>>>();
+    if stacks.iter().all(|stack| stack.is_empty()) {
+        stacks[0].push(char::from(b'A' + random.below(26) as u8).to_string());
+    }
+
+    let keys = (1..=stack_count).map(|key| key.to_string());
+    let crate_stacks = CrateStacks::new(keys.zip(stacks.iter().cloned()).collect());
+    let mut output = format!("{}\n\n", crate_stacks);
+
+    for _ in 0..size {
+        let non_empty = (0..stack_count)
+            .filter(|index| !stacks[*index].is_empty())
+            .collect::<Vec<usize>>();
+        let from = *random.choose(&non_empty);
+        let to = (from + 1 + random.below(stack_count - 1)) % stack_count;
+        let count = 1 + random.below(stacks[from].len());
+        let split_at = stacks[from].len() - count;
+        let moved = stacks[from].split_off(split_at);
+        stacks[to].extend(moved);
+        output.push_str(&format!("move {} from {} to {}\n", count, from + 1, to + 1));
+    }
+
+    output
 }
 
 #[cfg(test)]
@@ -68,4 +140,84 @@ move 1 from 1 to 2
         assert_eq!("MCD", output);
         Ok(())
     }
+
+    #[test]
+    fn stacks_with_long_keys_and_labels() -> Result<(), Box<dyn Error>> {
+        let file_contents = "\
+[AB]                                    [XY]
+ [C] [D] [E] [F] [G] [H] [I] [J] [K] [L] [M]
+  1   2   3   4   5   6   7   8   9  10  11
+
+move 2 from 11 to 10
+move 1 from 1 to 10
+";
+        let output = supply_stacks(PuzzleInput {
+            file_contents: file_contents.to_string(),
+            puzzle_part: PuzzlePart::Part2,
+        })?;
+
+        assert_eq!("CDEFGHIJKAB", output);
+        Ok(())
+    }
+
+    #[test]
+    fn moving_too_many_crates_should_fail_with_line() {
+        let file_contents = INPUT_TEXT.replace("move 2 from 2 to 1", "move 5 from 2 to 1");
+        let error = supply_stacks(PuzzleInput {
+            file_contents,
+            puzzle_part: PuzzlePart::Part1,
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            "line 8: move 5 from 2 but stack 2 has 2 crates\n",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_instruction_should_fail_with_line() {
+        let file_contents = INPUT_TEXT.replace("move 3 from 1 to 3", "move x from 1 to 3");
+        let error = supply_stacks(PuzzleInput {
+            file_contents,
+            puzzle_part: PuzzlePart::Part1,
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            "Error in second part: line 7: invalid value 'x' for count\n\n\n",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trace_should_draw_stacks_after_every_nth_move() -> Result<(), Box<dyn Error>> {
+        let output = supply_stacks_report(ReportInput::new("trace", &["2", "3"], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+before the first move:
+    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+after move 3 (move 2 from 2 to 1):
+        [D]
+        [N]
+[C]     [Z]
+[M]     [P]
+ 1   2   3 
+
+after move 4 (move 1 from 1 to 2):
+        [D]
+        [N]
+        [Z]
+[M] [C] [P]
+ 1   2   3 
+",
+            output
+        );
+        Ok(())
+    }
 }
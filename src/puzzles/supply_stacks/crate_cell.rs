@@ -1,56 +1,99 @@
 use std::{
     error::Error,
     fmt::{self, Debug, Display, Formatter},
-    str::FromStr,
+    ops::Range,
 };
 
 pub enum ParseCrateCellError {
-    InvalidCell { input: String },
+    UnexpectedChar { column: usize, c: char },
+    EmptyLabel { column: usize },
+    UnclosedCrate { column: usize },
 }
 
 impl Display for ParseCrateCellError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Self::InvalidCell { input } => write!(f, "invalid cell '{}'", input),
+            Self::UnexpectedChar { column, c } => {
+                write!(f, "unexpected '{}' in column {}", c, column + 1)
+            }
+            Self::EmptyLabel { column } => {
+                write!(f, "crate in column {} has no label", column + 1)
+            }
+            Self::UnclosedCrate { column } => {
+                write!(f, "crate in column {} is not closed", column + 1)
+            }
         }
     }
 }
 
 impl Debug for ParseCrateCellError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        <Self as Display>::fmt(&self, f)
+        <Self as Display>::fmt(self, f)
     }
 }
 
 impl Error for ParseCrateCellError {}
 
-pub enum CrateCell {
-    Empty,
-    Crate(char),
-    Stack(char),
+/// A crate or a stack key in a drawing, with the character columns that it spans.
+pub struct CrateCell {
+    pub columns: Range<usize>,
+    pub label: String,
 }
 
-impl FromStr for CrateCell {
-    type Err = ParseCrateCellError;
+impl CrateCell {
+    pub fn overlaps(&self, other: &CrateCell) -> bool {
+        self.columns.start < other.columns.end && other.columns.start < self.columns.end
+    }
+
+    /// Parses a row of crates such as `[A]     [BC]`, with any number of spaces between them.
+    pub fn parse_crates(line: &str) -> Result<Vec<CrateCell>, ParseCrateCellError> {
+        let mut cells: Vec<CrateCell> = Vec::new();
+        let mut open: Option<(usize, String)> = None;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let chars = s.chars().collect::<Vec<char>>();
-        if chars.len() != 3 {
-            return Err(Self::Err::InvalidCell {
-                input: s.to_string(),
-            });
+        for (column, c) in line.chars().enumerate() {
+            open = match (open, c) {
+                (None, ' ') => None,
+                (None, '[') => Some((column, String::new())),
+                (Some((start, label)), ']') => {
+                    if label.is_empty() {
+                        return Err(ParseCrateCellError::EmptyLabel { column: start });
+                    }
+                    cells.push(CrateCell {
+                        columns: start..(column + 1),
+                        label,
+                    });
+                    None
+                }
+                (Some((start, mut label)), c) if !c.is_whitespace() && c != '[' => {
+                    label.push(c);
+                    Some((start, label))
+                }
+                _ => return Err(ParseCrateCellError::UnexpectedChar { column, c }),
+            };
         }
 
-        if chars.iter().all(|c| c == &' ') {
-            Ok(Self::Empty)
-        } else if chars[0] == ' ' && chars[2] == ' ' {
-            Ok(Self::Stack(chars[1]))
-        } else if chars[0] == '[' && chars[2] == ']' {
-            Ok(Self::Crate(chars[1]))
-        } else {
-            Err(Self::Err::InvalidCell {
-                input: s.to_string(),
-            })
+        match open {
+            Some((start, _)) => Err(ParseCrateCellError::UnclosedCrate { column: start }),
+            None => Ok(cells),
+        }
+    }
+
+    /// Parses the row of stack keys at the bottom of a drawing, such as ` 1   2  10 `.
+    pub fn parse_keys(line: &str) -> Vec<CrateCell> {
+        let mut cells: Vec<CrateCell> = Vec::new();
+        for (column, c) in line.chars().enumerate() {
+            match cells.last_mut() {
+                _ if c.is_whitespace() => {}
+                Some(cell) if cell.columns.end == column => {
+                    cell.columns.end += 1;
+                    cell.label.push(c);
+                }
+                _ => cells.push(CrateCell {
+                    columns: column..(column + 1),
+                    label: c.to_string(),
+                }),
+            }
         }
+        cells
     }
 }
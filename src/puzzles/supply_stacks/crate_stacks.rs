@@ -1,8 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    str::FromStr,
+};
 
-use crate::{common::position::Position, parse::grid::Grid};
-
-use super::{crate_cell::CrateCell, move_instruction::MoveInstruction};
+use super::{
+    crate_cell::{CrateCell, ParseCrateCellError},
+    move_instruction::MoveInstruction,
+};
 
 #[derive(Clone, Copy)]
 pub enum MoveMode {
@@ -10,88 +16,354 @@ pub enum MoveMode {
     AllAtOnce,
 }
 
+pub enum MoveError {
+    UnknownStack(String),
+    NotEnoughCrates {
+        count: usize,
+        from: String,
+        available: usize,
+    },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownStack(key) => write!(f, "there is no stack {}", key),
+            Self::NotEnoughCrates {
+                count,
+                from,
+                available,
+            } => write!(
+                f,
+                "move {} from {} but stack {} has {} crates",
+                count, from, from, available
+            ),
+        }
+    }
+}
+
+impl Debug for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for MoveError {}
+
+pub enum ParseCrateStacksError {
+    Empty,
+    Row {
+        line: usize,
+        error: ParseCrateCellError,
+    },
+    UnmatchedCrate {
+        line: usize,
+        label: String,
+    },
+    AmbiguousCrate {
+        line: usize,
+        label: String,
+    },
+    StackedSideBySide {
+        line: usize,
+        key: String,
+    },
+    DuplicateStackKey(String),
+}
+
+impl Display for ParseCrateStacksError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "unexpected empty drawing"),
+            Self::Row { line, error } => write!(f, "line {}: {}", line, error),
+            Self::UnmatchedCrate { line, label } => write!(
+                f,
+                "line {}: crate {} is not above any stack key",
+                line, label
+            ),
+            Self::AmbiguousCrate { line, label } => write!(
+                f,
+                "line {}: crate {} is above several stack keys",
+                line, label
+            ),
+            Self::StackedSideBySide { line, key } => write!(
+                f,
+                "line {}: stack {} has several crates on the same level",
+                line, key
+            ),
+            Self::DuplicateStackKey(key) => write!(f, "stack key {} is used twice", key),
+        }
+    }
+}
+
+impl Debug for ParseCrateStacksError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for ParseCrateStacksError {}
+
+#[derive(Debug, PartialEq)]
 pub struct CrateStacks {
-    stack_map: Vec<char>,
-    stacks: HashMap<char, Vec<char>>,
+    stack_map: Vec<String>,
+    stacks: HashMap<String, Vec<String>>,
 }
 
-impl From<Grid<3, 1, CrateCell>> for CrateStacks {
-    fn from(grid: Grid<3, 1, CrateCell>) -> Self {
-        let mut stack_map: Vec<char> = Vec::new();
-        let mut stacks: HashMap<char, Vec<char>> = HashMap::new();
-
-        for x in 0..(grid.width()) {
-            let key = if let CrateCell::Stack(key) = grid.get_value(Position {
-                x,
-                y: grid.height() - 1,
-            }) {
-                *key
-            } else {
-                unreachable!()
-            };
-
-            stack_map.push(key);
-
-            let crates: Vec<char> = (0..(grid.height() - 1))
-                .rev()
-                .map(|y| grid.get_value(Position { x, y }))
-                .filter_map(|cell| match cell {
-                    CrateCell::Empty => None,
-                    CrateCell::Crate(x) => Some(*x),
-                    CrateCell::Stack(_) => unreachable!(),
-                })
-                .collect::<Vec<char>>();
+impl FromStr for CrateStacks {
+    type Err = ParseCrateStacksError;
 
-            stacks.insert(key, crates);
+    /// Parses a drawing of crates over a line of stack keys. Every crate belongs to the stack whose
+    /// key shares a column with it, so crates and keys can be of any width.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().collect::<Vec<&str>>();
+        let (footer, rows) = lines.split_last().ok_or(Self::Err::Empty)?;
+        let keys = CrateCell::parse_keys(footer);
+
+        let mut stacks: HashMap<String, Vec<String>> = HashMap::new();
+        for key in keys.iter() {
+            if stacks.insert(key.label.clone(), Vec::new()).is_some() {
+                return Err(Self::Err::DuplicateStackKey(key.label.clone()));
+            }
         }
 
-        CrateStacks { stack_map, stacks }
+        for (index, row) in rows.iter().enumerate().rev() {
+            let line = index + 1;
+            let crates =
+                CrateCell::parse_crates(row).map_err(|error| Self::Err::Row { line, error })?;
+            let mut filled: Vec<&str> = Vec::new();
+            for crate_cell in crates {
+                let mut matching = keys.iter().filter(|key| key.overlaps(&crate_cell));
+                let key = match (matching.next(), matching.next()) {
+                    (Some(key), None) => key.label.as_str(),
+                    (None, _) => {
+                        return Err(Self::Err::UnmatchedCrate {
+                            line,
+                            label: crate_cell.label,
+                        })
+                    }
+                    (Some(_), Some(_)) => {
+                        return Err(Self::Err::AmbiguousCrate {
+                            line,
+                            label: crate_cell.label,
+                        })
+                    }
+                };
+                if filled.contains(&key) {
+                    return Err(Self::Err::StackedSideBySide {
+                        line,
+                        key: key.to_string(),
+                    });
+                }
+                filled.push(key);
+                stacks.get_mut(key).unwrap().push(crate_cell.label);
+            }
+        }
+
+        Ok(CrateStacks {
+            stack_map: keys.into_iter().map(|key| key.label).collect(),
+            stacks,
+        })
     }
 }
 
-impl CrateStacks {
-    pub fn perform_instructions(
-        &mut self,
-        instructions: &Vec<MoveInstruction>,
-        move_mode: MoveMode,
-    ) {
-        for instruction in instructions {
-            self.perform_instruction(instruction, move_mode);
+/// Centres a crate or a key in a column, with the extra space on the left when it cannot be split
+/// evenly. Centring crates and keys alike keeps every crate above the key of its stack.
+fn centre(text: &str, width: usize) -> String {
+    let padding = (width - text.chars().count()).div_ceil(2);
+    format!("{:<width$}", " ".repeat(padding) + text, width = width)
+}
+
+/// Draws the stacks in the format of the puzzle input, with a line of stack keys at the bottom.
+/// Every stack gets a column as wide as its widest crate, or its key with a space on both sides.
+impl Display for CrateStacks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let widths = self
+            .stack_map
+            .iter()
+            .map(|key| {
+                self.stacks[key]
+                    .iter()
+                    .map(|label| label.chars().count() + 2)
+                    .chain([key.chars().count() + 2])
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<usize>>();
+
+        let height = self.stacks.values().map(|stack| stack.len()).max();
+        for level in (0..height.unwrap_or(0)).rev() {
+            let row = self
+                .stack_map
+                .iter()
+                .zip(widths.iter())
+                .map(|(key, width)| match self.stacks[key].get(level) {
+                    Some(label) => centre(&format!("[{}]", label), *width),
+                    None => " ".repeat(*width),
+                })
+                .collect::<Vec<String>>();
+            writeln!(f, "{}", row.join(" "))?;
         }
+        let footer = self
+            .stack_map
+            .iter()
+            .zip(widths.iter())
+            .map(|(key, width)| centre(key, *width))
+            .collect::<Vec<String>>();
+        write!(f, "{}", footer.join(" "))
     }
+}
 
+impl CrateStacks {
     pub fn get_stack_tops(&self) -> String {
         self.stack_map
             .iter()
-            .filter_map(|c| self.stacks.get(c))
+            .filter_map(|key| self.stacks.get(key))
             .filter_map(|stack| stack.last())
+            .map(|label| label.as_str())
             .collect::<String>()
     }
 
-    fn perform_instruction(&mut self, instruction: &MoveInstruction, move_mode: MoveMode) {
-        let mut buffer: Vec<char> = Vec::new();
+    /// Builds stacks from their keys and crates, listed from the bottom up.
+    pub fn new(stacks: Vec<(String, Vec<String>)>) -> CrateStacks {
+        CrateStacks {
+            stack_map: stacks.iter().map(|(key, _)| key.clone()).collect(),
+            stacks: stacks.into_iter().collect(),
+        }
+    }
 
-        if let Some(source) = self.stacks.get_mut(&instruction.from) {
-            for _ in 0..(instruction.count) {
-                if let Some(x) = source.pop() {
-                    buffer.push(x);
-                }
-            }
+    /// Moves crates between stacks, or leaves the stacks as they are if the instruction cannot be
+    /// performed.
+    pub fn perform_instruction(
+        &mut self,
+        instruction: &MoveInstruction,
+        move_mode: MoveMode,
+    ) -> Result<(), MoveError> {
+        if !self.stacks.contains_key(&instruction.to) {
+            return Err(MoveError::UnknownStack(instruction.to.clone()));
+        }
+        let source = self
+            .stacks
+            .get_mut(&instruction.from)
+            .ok_or_else(|| MoveError::UnknownStack(instruction.from.clone()))?;
+        if source.len() < instruction.count {
+            return Err(MoveError::NotEnoughCrates {
+                count: instruction.count,
+                from: instruction.from.clone(),
+                available: source.len(),
+            });
         }
 
-        if let Some(target) = self.stacks.get_mut(&instruction.to) {
-            match move_mode {
-                MoveMode::OneByOne => {
-                    for item in buffer.into_iter() {
-                        target.push(item);
-                    }
-                }
-                MoveMode::AllAtOnce => {
-                    for item in buffer.into_iter().rev() {
-                        target.push(item);
-                    }
-                }
-            }
+        let mut moved = source.split_off(source.len() - instruction.count);
+        if let MoveMode::OneByOne = move_mode {
+            moved.reverse();
         }
+        self.stacks.get_mut(&instruction.to).unwrap().extend(moved);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    const DRAWING: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ";
+
+    #[test]
+    fn drawing_should_round_trip() -> Result<(), Box<dyn Error>> {
+        let crate_stacks = DRAWING.parse::<CrateStacks>()?;
+
+        assert_eq!(DRAWING, crate_stacks.to_string());
+        assert_eq!(
+            crate_stacks,
+            crate_stacks.to_string().parse::<CrateStacks>()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn intermediate_state_should_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut crate_stacks = DRAWING.parse::<CrateStacks>()?;
+        crate_stacks.perform_instruction(&"move 3 from 2 to 1".parse()?, MoveMode::AllAtOnce)?;
+
+        let expected = CrateStacks::new(vec![
+            stack("1", &["Z", "N", "M", "C", "D"]),
+            stack("2", &[]),
+            stack("3", &["P"]),
+        ]);
+        assert_eq!(expected, crate_stacks);
+        assert_eq!(expected, crate_stacks.to_string().parse::<CrateStacks>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn wide_crates_and_keys_should_round_trip() -> Result<(), Box<dyn Error>> {
+        let crate_stacks = CrateStacks::new(vec![
+            stack("1", &["AB"]),
+            stack("2", &[]),
+            stack("10", &["C", "DEF"]),
+            stack("11", &["G"]),
+        ]);
+
+        let drawing = crate_stacks.to_string();
+        assert_eq!(
+            "         [DEF]     \n[AB]      [C]   [G]\n  1   2    10   11 ",
+            drawing
+        );
+        assert_eq!(crate_stacks, drawing.parse::<CrateStacks>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn narrow_crates_under_wide_ones_should_round_trip() -> Result<(), Box<dyn Error>> {
+        let crate_stacks = CrateStacks::new(vec![
+            stack("1", &["X", "ABCDEFG"]),
+            stack("2", &["AB", "C", "DEFG"]),
+        ]);
+
+        let drawing = crate_stacks.to_string();
+        assert_eq!(
+            "          [DEFG]\n[ABCDEFG]   [C] \n   [X]     [AB] \n    1        2  ",
+            drawing
+        );
+        assert_eq!(crate_stacks, drawing.parse::<CrateStacks>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn crates_should_belong_to_the_key_below_them() -> Result<(), Box<dyn Error>> {
+        let crate_stacks = "[AB]\n [C] [D]\n  x    y".parse::<CrateStacks>()?;
+
+        assert_eq!(
+            CrateStacks::new(vec![stack("x", &["C", "AB"]), stack("y", &["D"])]),
+            crate_stacks
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn crates_should_be_above_a_single_key() {
+        let unmatched = "[A]     [B]\n 1   2".parse::<CrateStacks>().unwrap_err();
+        let ambiguous = "[ABC]\n 1 2".parse::<CrateStacks>().unwrap_err();
+
+        assert_eq!(
+            "line 1: crate B is not above any stack key",
+            unmatched.to_string()
+        );
+        assert_eq!(
+            "line 1: crate ABC is above several stack keys",
+            ambiguous.to_string()
+        );
+    }
+
+    fn stack(key: &str, labels: &[&str]) -> (String, Vec<String>) {
+        (
+            key.to_string(),
+            labels.iter().map(|label| label.to_string()).collect(),
+        )
     }
 }
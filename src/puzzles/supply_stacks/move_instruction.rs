@@ -7,8 +7,6 @@ use std::{
 pub enum ParseMoveInstructionError {
     InvalidFormat,
     InvalidCount { input: String },
-    InvalidFrom { input: String },
-    InvalidTo { input: String },
 }
 
 impl Display for ParseMoveInstructionError {
@@ -16,8 +14,6 @@ impl Display for ParseMoveInstructionError {
         match self {
             Self::InvalidFormat => write!(f, "invalid format"),
             Self::InvalidCount { input } => write!(f, "invalid value '{}' for count", input),
-            Self::InvalidFrom { input } => write!(f, "invalid value '{}' for from", input),
-            Self::InvalidTo { input } => write!(f, "invalid value '{}' for to", input),
         }
     }
 }
@@ -32,8 +28,14 @@ impl Error for ParseMoveInstructionError {}
 
 pub struct MoveInstruction {
     pub count: usize,
-    pub from: char,
-    pub to: char,
+    pub from: String,
+    pub to: String,
+}
+
+impl Display for MoveInstruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
 }
 
 impl FromStr for MoveInstruction {
@@ -50,14 +52,8 @@ impl FromStr for MoveInstruction {
             .map_err(|_| Self::Err::InvalidCount {
                 input: parts[1].to_string(),
             })?;
-        let from = parts[3]
-            .parse::<char>()
-            .map_err(|_| Self::Err::InvalidFrom {
-                input: parts[3].to_string(),
-            })?;
-        let to = parts[5].parse::<char>().map_err(|_| Self::Err::InvalidTo {
-            input: parts[5].to_string(),
-        })?;
+        let from = parts[3].to_string();
+        let to = parts[5].to_string();
         Ok(MoveInstruction { count, from, to })
     }
 }
@@ -0,0 +1,102 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use crate::parse::{
+    error::ParseContentsError,
+    lines::ByLines,
+    section_pair::{ParseSectionPairError, SectionPair},
+    sections::AsSections,
+};
+
+use super::{
+    crate_stacks::{CrateStacks, MoveError, MoveMode},
+    move_instruction::MoveInstruction,
+};
+
+pub struct InstructionError {
+    pub line: usize,
+    pub error: MoveError,
+}
+
+impl Display for InstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl Debug for InstructionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for InstructionError {}
+
+impl From<InstructionError> for ParseContentsError {
+    fn from(value: InstructionError) -> Self {
+        ParseContentsError::new(value)
+    }
+}
+
+/// The starting stacks and the instructions of the crane operator, along with the line on which
+/// the instructions start.
+pub struct Procedure {
+    pub crate_stacks: CrateStacks,
+    instructions: Vec<MoveInstruction>,
+    first_line: usize,
+}
+
+impl Procedure {
+    /// Parses the drawing and the instructions. Errors in the instructions are reported with their
+    /// line in the whole input, numbered from 1 like the errors in performing them.
+    pub fn parse(file_contents: &str) -> Result<Procedure, ParseContentsError> {
+        let first_line = file_contents
+            .sections()
+            .nth(1)
+            .map_or(0, |section| section.starts_at_line)
+            + 1;
+        let SectionPair(crate_stacks, ByLines(instructions)) = file_contents
+            .parse::<SectionPair<CrateStacks, ByLines<MoveInstruction>>>()
+            .map_err(|error| match error {
+                ParseSectionPairError::SectionsParseError { first, second } => {
+                    ParseSectionPairError::SectionsParseError {
+                        first,
+                        second: second.map(|error| error.numbered_from(first_line)),
+                    }
+                }
+                error => error,
+            })?;
+
+        Ok(Procedure {
+            crate_stacks,
+            instructions,
+            first_line,
+        })
+    }
+
+    pub fn instruction_count(&self) -> usize {
+        self.instructions.len()
+    }
+
+    /// Performs the instructions in order, calling `after_instruction` with the number of
+    /// instructions performed so far after each of them. Stops at the first instruction that cannot
+    /// be performed.
+    pub fn perform_instructions(
+        &mut self,
+        move_mode: MoveMode,
+        mut after_instruction: impl FnMut(usize, &MoveInstruction, &CrateStacks),
+    ) -> Result<(), InstructionError> {
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            self.crate_stacks
+                .perform_instruction(instruction, move_mode)
+                .map_err(|error| InstructionError {
+                    line: self.first_line + index,
+                    error,
+                })?;
+            after_instruction(index + 1, instruction, &self.crate_stacks);
+        }
+        Ok(())
+    }
+}
@@ -1,41 +1,193 @@
 mod digit_grid;
 
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display, Formatter, Write},
+    str::FromStr,
+};
+
 use crate::{
-    common::position::Position,
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
     parse::{error::ParseContentsError, grid::Grid},
 };
 
-use self::digit_grid::TreetopGrid;
+use self::digit_grid::{DirectionFrom, TreetopGrid};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-// PERF
-// Part 1 can be done with 4 scans of the grid, instead of recomputing each visibility each time.
-// Part 2 can probably done more efficiently as well, checking by line / column for a part of the scenic score.
-// TARGET
-// Part 1: ~1 ms (is ~6 ms)
-// Part 2: ~1 ms (?, is ~6 ms)
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn treetop_tree_house(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let grid = input.file_contents.parse::<Grid<1, 0, usize>>()?;
-    let grid_positions = grid.positions().collect::<Vec<Position<usize>>>();
-    let treetop_grid = TreetopGrid(grid);
+    let views = TreetopGrid(grid).views();
     let answer = match input.puzzle_part {
-        PuzzlePart::Part1 => grid_positions
+        PuzzlePart::Part1 => views
+            .visibility_grid()
             .into_iter()
-            .filter(|position| treetop_grid.visible(*position))
+            .flatten()
+            .filter(|visible| *visible)
             .count(),
-        PuzzlePart::Part2 => grid_positions
+        PuzzlePart::Part2 => views
+            .scenic_score_grid()
             .into_iter()
-            .map(|position| treetop_grid.scenic_score(position))
+            .flatten()
             .max()
             .map_or(0, |x| x),
     };
     Ok(answer.to_string())
 }
 
+pub enum ParseHeatmapFormatError {
+    UnknownFormat(String),
+}
+
+impl Display for ParseHeatmapFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat(input) => write!(f, "unknown heatmap format '{}'", input),
+        }
+    }
+}
+
+impl Debug for ParseHeatmapFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for ParseHeatmapFormatError {}
+
+enum HeatmapFormat {
+    Ascii,
+    Pgm,
+}
+
+impl FromStr for HeatmapFormat {
+    type Err = ParseHeatmapFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ascii" => Ok(Self::Ascii),
+            "pgm" => Ok(Self::Pgm),
+            _ => Err(Self::Err::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// Characters from the lowest to the highest scenic scores in an ASCII heatmap.
+const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Reports on the scenic scores of the trees. The modes are:
+/// - `heatmap [format]`: the scenic scores as a heatmap, either as `ascii` shading (the default)
+///   or as a `pgm` image with scores scaled to 0 to 255.
+/// - `best`: the tree with the highest scenic score, with its viewing distance in every direction.
+pub fn treetop_tree_house_report(input: ReportInput) -> Result<String, ReportError> {
+    let grid = input.file_contents.parse::<Grid<1, 0, usize>>()?;
+    let treetop_grid = TreetopGrid(grid);
+    let views = treetop_grid.views();
+    let scores = views.scenic_score_grid();
+    let maximum = scores.iter().flatten().copied().max().unwrap_or(0);
+
+    let mut output = String::new();
+    match input.mode.as_str() {
+        "heatmap" => match input
+            .argument::<HeatmapFormat>(0, "format")?
+            .unwrap_or(HeatmapFormat::Ascii)
+        {
+            HeatmapFormat::Ascii => {
+                for row in scores.iter() {
+                    let line = row
+                        .iter()
+                        .map(|score| SHADES[score * (SHADES.len() - 1) / maximum.max(1)])
+                        .collect::<String>();
+                    writeln!(output, "{}", line).unwrap();
+                }
+            }
+            HeatmapFormat::Pgm => {
+                writeln!(output, "P2").unwrap();
+                writeln!(
+                    output,
+                    "{} {}",
+                    treetop_grid.0.width(),
+                    treetop_grid.0.height()
+                )
+                .unwrap();
+                writeln!(output, "255").unwrap();
+                for row in scores.iter() {
+                    let line = row
+                        .iter()
+                        .map(|score| (score * 255 / maximum.max(1)).to_string())
+                        .collect::<Vec<String>>();
+                    writeln!(output, "{}", line.join(" ")).unwrap();
+                }
+            }
+        },
+        "best" => {
+            let best = treetop_grid
+                .0
+                .positions()
+                .find(|position| scores[position.y][position.x] == maximum);
+            let Some(position) = best else {
+                return Ok(String::from("there are no trees\n"));
+            };
+            writeln!(
+                output,
+                "the tree in row {}, column {} has height {}",
+                position.y + 1,
+                position.x + 1,
+                treetop_grid.0.get_value(position)
+            )
+            .unwrap();
+            let distances = DirectionFrom::all()
+                .into_iter()
+                .map(|from| (from, views.viewing_distance(position, from)))
+                .collect::<Vec<(DirectionFrom, usize)>>();
+            for (from, distance) in distances.iter() {
+                writeln!(
+                    output,
+                    "looking {}, it sees {} tree{}",
+                    from,
+                    distance,
+                    if *distance == 1 { "" } else { "s" }
+                )
+                .unwrap();
+            }
+            let factors = distances
+                .iter()
+                .map(|(_, distance)| distance.to_string())
+                .collect::<Vec<String>>();
+            writeln!(
+                output,
+                "scenic score: {} = {}",
+                factors.join(" * "),
+                views.scenic_score(position)
+            )
+            .unwrap();
+        }
+        _ => return Err(input.unknown_mode(&["heatmap", "best"])),
+    }
+    Ok(output)
+}
+
+/// Generates a `size` by `size` grid of tree heights.
+pub fn treetop_tree_house_generate(random: &mut Random, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| {
+            let mut line = (0..size)
+                .map(|_| char::from(b'0' + random.below(10) as u8))
+                .collect::<String>();
+            line.push('\n');
+            line
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +221,44 @@ mod tests {
         assert_eq!("8", output);
         Ok(())
     }
+
+    #[test]
+    fn best_should_explain_the_scenic_score() -> Result<(), Box<dyn Error>> {
+        let output = treetop_tree_house_report(ReportInput::new("best", &[], INPUT_TEXT))?;
+
+        assert_eq!(
+            "\
+the tree in row 4, column 3 has height 5
+looking up, it sees 2 trees
+looking down, it sees 1 tree
+looking left, it sees 2 trees
+looking right, it sees 2 trees
+scenic score: 2 * 1 * 2 * 2 = 8
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn heatmap_should_shade_scenic_scores() -> Result<(), Box<dyn Error>> {
+        let ascii = treetop_tree_house_report(ReportInput::new("heatmap", &[], INPUT_TEXT))?;
+        let pgm = treetop_tree_house_report(ReportInput::new("heatmap", &["pgm"], INPUT_TEXT))?;
+
+        assert_eq!("     \n .=. \n *.: \n .@- \n     \n", ascii);
+        assert_eq!(
+            "\
+P2
+5 5
+255
+0 0 0 0 0
+0 31 127 31 0
+0 191 31 63 0
+0 31 255 95 0
+0 0 0 0 0
+",
+            pgm
+        );
+        Ok(())
+    }
 }
@@ -1,7 +1,8 @@
-use std::cmp::min;
+use std::fmt::{self, Display, Formatter};
 
 use crate::{common::position::Position, parse::grid::Grid};
 
+#[derive(Debug, Clone, Copy)]
 pub enum DirectionFrom {
     Top,
     Bottom,
@@ -10,7 +11,7 @@ pub enum DirectionFrom {
 }
 
 impl DirectionFrom {
-    fn all() -> Vec<DirectionFrom> {
+    pub fn all() -> Vec<DirectionFrom> {
         vec![
             DirectionFrom::Top,
             DirectionFrom::Bottom,
@@ -20,75 +21,189 @@ impl DirectionFrom {
     }
 }
 
+impl Display for DirectionFrom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Top => write!(f, "up"),
+            Self::Bottom => write!(f, "down"),
+            Self::Left => write!(f, "left"),
+            Self::Right => write!(f, "right"),
+        }
+    }
+}
+
 pub struct TreetopGrid(pub Grid<1, 0, usize>);
 
 impl TreetopGrid {
-    pub fn visible(&self, position: Position<usize>) -> bool {
-        DirectionFrom::all()
-            .into_iter()
-            .any(|from| self.visible_from(position, from))
+    /// Computes the views from all trees at once, with a sweep per direction over every row or
+    /// column.
+    pub fn views(&self) -> TreeViews {
+        let size = self.0.width() * self.0.height();
+        let mut views = TreeViews {
+            width: self.0.width(),
+            viewing_distances: DirectionFrom::all()
+                .into_iter()
+                .map(|_| vec![0; size])
+                .collect(),
+            visible: vec![false; size],
+        };
+        for from in DirectionFrom::all() {
+            for line in self.lines(from) {
+                self.sweep(&line, from, &mut views);
+            }
+        }
+        views
+    }
+
+    /// Returns the indices of the trees in every row or column, starting at the edge that trees
+    /// are looked at from.
+    fn lines(&self, from: DirectionFrom) -> Vec<Vec<usize>> {
+        let (width, height) = (self.0.width(), self.0.height());
+        match from {
+            DirectionFrom::Left => (0..height)
+                .map(|y| (0..width).map(|x| y * width + x).collect())
+                .collect(),
+            DirectionFrom::Right => (0..height)
+                .map(|y| (0..width).rev().map(|x| y * width + x).collect())
+                .collect(),
+            DirectionFrom::Top => (0..width)
+                .map(|x| (0..height).map(|y| y * width + x).collect())
+                .collect(),
+            DirectionFrom::Bottom => (0..width)
+                .map(|x| (0..height).rev().map(|y| y * width + x).collect())
+                .collect(),
+        }
+    }
+
+    /// Walks along a line away from its edge, keeping a stack of the trees that are not hidden
+    /// behind a later tree at least as high. The top of the stack that remains after popping the
+    /// lower ones is the tree that blocks the view towards the edge.
+    fn sweep(&self, line: &[usize], from: DirectionFrom, views: &mut TreeViews) {
+        let height_at = |step: usize| {
+            let index = line[step];
+            *self.0.get_value(Position {
+                x: index % self.0.width(),
+                y: index / self.0.width(),
+            })
+        };
+        let mut stack: Vec<usize> = Vec::new();
+        for (step, index) in line.iter().copied().enumerate() {
+            let height = height_at(step);
+            while stack.last().is_some_and(|top| height_at(*top) < height) {
+                stack.pop();
+            }
+            match stack.last() {
+                Some(blocking) => views.viewing_distances[from as usize][index] = step - blocking,
+                None => {
+                    views.viewing_distances[from as usize][index] = step;
+                    views.visible[index] = true;
+                }
+            }
+            stack.push(step);
+        }
+    }
+}
+
+/// How far the view from every tree reaches in every direction, and whether every tree can be seen
+/// from outside the grid.
+pub struct TreeViews {
+    width: usize,
+    viewing_distances: Vec<Vec<usize>>,
+    visible: Vec<bool>,
+}
+
+impl TreeViews {
+    pub fn viewing_distance(&self, position: Position<usize>, from: DirectionFrom) -> usize {
+        self.viewing_distances[from as usize][position.y * self.width + position.x]
+    }
+
+    /// Returns, row by row, whether the trees can be seen from outside the grid.
+    pub fn visibility_grid(&self) -> Vec<Vec<bool>> {
+        self.visible
+            .chunks(self.width.max(1))
+            .map(|row| row.to_vec())
+            .collect()
     }
 
     pub fn scenic_score(&self, position: Position<usize>) -> usize {
         DirectionFrom::all()
             .into_iter()
-            .map(|from| self.visible_tree_count_from(position, from))
+            .map(|from| self.viewing_distance(position, from))
             .product()
     }
 
-    fn visible_from(&self, position: Position<usize>, from: DirectionFrom) -> bool {
-        let compare_with = self.trees_from_direction(position, from);
-
-        self.is_higher_than(position, &mut compare_with.into_iter())
+    /// Returns, row by row, the product of the viewing distances of the trees.
+    pub fn scenic_score_grid(&self) -> Vec<Vec<usize>> {
+        let height = self.visible.len() / self.width.max(1);
+        (0..height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| self.scenic_score(Position { x, y }))
+                    .collect()
+            })
+            .collect()
     }
+}
 
-    fn visible_tree_count_from(&self, position: Position<usize>, from: DirectionFrom) -> usize {
-        let compare_with = self.trees_from_direction(position, from);
-
-        self.visible_tree_count(position, compare_with)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{common::random::Random, puzzles::treetop_tree_house::treetop_tree_house_generate};
+    use std::error::Error;
 
-    fn trees_from_direction(
-        &self,
+    /// Looks along every ray from the tree, as a reference for the sweeps.
+    fn viewing_distance_by_ray(
+        grid: &TreetopGrid,
         position: Position<usize>,
         from: DirectionFrom,
-    ) -> Vec<Position<usize>> {
-        match from {
+    ) -> (usize, bool) {
+        let ray = match from {
             DirectionFrom::Left => (0..position.x)
                 .rev()
                 .map(|x| Position { x, y: position.y })
                 .collect::<Vec<Position<usize>>>(),
-            DirectionFrom::Right => ((position.x + 1)..(self.0.width()))
+            DirectionFrom::Right => ((position.x + 1)..grid.0.width())
                 .map(|x| Position { x, y: position.y })
-                .collect::<Vec<Position<usize>>>(),
+                .collect(),
             DirectionFrom::Top => (0..position.y)
                 .rev()
                 .map(|y| Position { x: position.x, y })
-                .collect::<Vec<Position<usize>>>(),
-            DirectionFrom::Bottom => ((position.y + 1)..(self.0.height()))
+                .collect(),
+            DirectionFrom::Bottom => ((position.y + 1)..grid.0.height())
                 .map(|y| Position { x: position.x, y })
-                .collect::<Vec<Position<usize>>>(),
+                .collect(),
+        };
+        let height = grid.0.get_value(position);
+        match ray.iter().position(|p| grid.0.get_value(*p) >= height) {
+            Some(blocking) => (blocking + 1, false),
+            None => (ray.len(), true),
         }
     }
 
-    fn is_higher_than<T>(&self, position: Position<usize>, compare_with: &mut T) -> bool
-    where
-        T: Iterator<Item = Position<usize>>,
-    {
-        compare_with.all(|p| self.0.get_value(position) > self.0.get_value(p))
-    }
+    #[test]
+    fn sweeps_should_match_rays() -> Result<(), Box<dyn Error>> {
+        let mut random = Random::new(7);
+        for size in [1, 2, 5, 20] {
+            let grid = TreetopGrid(treetop_tree_house_generate(&mut random, size).parse()?);
+            let views = grid.views();
+            let visibility = views.visibility_grid();
+            let scores = views.scenic_score_grid();
 
-    fn visible_tree_count(
-        &self,
-        position: Position<usize>,
-        compare_with: Vec<Position<usize>>,
-    ) -> usize {
-        let count = compare_with.len();
-        let visible_trees = compare_with
-            .into_iter()
-            .take_while(|p| self.0.get_value(position) > self.0.get_value(*p))
-            .count()
-            + 1;
-        min(count, visible_trees)
+            for position in grid.0.positions() {
+                let rays = DirectionFrom::all()
+                    .into_iter()
+                    .map(|from| {
+                        let (distance, visible) = viewing_distance_by_ray(&grid, position, from);
+                        assert_eq!(distance, views.viewing_distance(position, from));
+                        (distance, visible)
+                    })
+                    .collect::<Vec<(usize, bool)>>();
+                let visible = rays.iter().any(|(_, visible)| *visible);
+                let score = rays.iter().map(|(distance, _)| distance).product::<usize>();
+                assert_eq!(visible, visibility[position.y][position.x]);
+                assert_eq!(score, scores[position.y][position.x]);
+            }
+        }
+        Ok(())
     }
 }
@@ -1,41 +1,105 @@
+mod marker;
+
+use std::{fmt::Write, num::NonZeroUsize};
+
+use self::marker::{first_marker, first_markers, markers};
 use crate::{
-    input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
-    parse::error::ParseContentsError,
+    common::random::Random,
+    input::{
+        puzzle_input::PuzzleInput,
+        puzzle_part::PuzzlePart,
+        report_input::{ReportError, ReportInput},
+    },
+    parse::{error::ParseContentsError, lines::ByLines},
 };
-use std::collections::{HashSet, VecDeque};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-fn deque_marks_start(deque: &VecDeque<char>, marker_len: usize) -> bool {
-    let mut hash_set: HashSet<char> = HashSet::new();
-    deque.iter().for_each(|c| {
-        hash_set.insert(*c);
-    });
-    deque.len() == marker_len && hash_set.len() == marker_len
-}
-
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn tuning_trouble(input: PuzzleInput) -> Result<String, ParseContentsError> {
     let marker_len = match input.puzzle_part {
         PuzzlePart::Part1 => 4,
         PuzzlePart::Part2 => 14,
     };
-    let mut chars = input.file_contents.chars();
-    let mut deque: VecDeque<char> = VecDeque::new();
-    let mut index: usize = 0;
-    while !deque_marks_start(&deque, marker_len) {
-        if let Some(c) = chars.next() {
-            index += 1;
-            deque.push_back(c);
-            if deque.len() > marker_len {
-                deque.pop_front();
+    let answer = first_marker(&input.file_contents, marker_len).map_err(ParseContentsError::new)?;
+    Ok(answer.to_string())
+}
+
+/// Reports on the markers in the datastream, of the length given as argument. The modes are:
+/// - `first <length>`: the first marker, or that there is none.
+/// - `all <length>`: every marker, including those that overlap others.
+/// - `streams`: the start-of-packet and start-of-message markers of every line, as a datastream of
+///   its own.
+pub fn tuning_trouble_report(input: ReportInput) -> Result<String, ReportError> {
+    let datastream = input.file_contents.chars().collect::<Vec<char>>();
+    let marker_text = |position: usize, length: usize| -> String {
+        datastream[(position - length)..position].iter().collect()
+    };
+    match input.mode.as_str() {
+        "first" => {
+            let length = input.required_argument::<NonZeroUsize>(0, "length")?.get();
+            Ok(match first_marker(&input.file_contents, length) {
+                Ok(position) => format!("{}: {}\n", position, marker_text(position, length)),
+                Err(error) => format!("{}\n", error),
+            })
+        }
+        "all" => {
+            let length = input.required_argument::<NonZeroUsize>(0, "length")?.get();
+            let positions = markers(&input.file_contents, length).collect::<Vec<usize>>();
+            let mut output = String::new();
+            for position in positions.iter() {
+                writeln!(output, "{}: {}", position, marker_text(*position, length)).unwrap();
             }
-        } else {
-            break;
+            writeln!(
+                output,
+                "{} markers of {} different characters",
+                positions.len(),
+                length
+            )
+            .unwrap();
+            Ok(output)
+        }
+        "streams" => {
+            let ByLines(datastreams) = input.file_contents.parse::<ByLines<String>>()?;
+            Ok(streams_report(&datastreams))
         }
+        _ => Err(input.unknown_mode(&["first", "all", "streams"])),
     }
-    Ok(index.to_string())
+}
+
+fn streams_report(datastreams: &[String]) -> String {
+    let mut output = String::new();
+    writeln!(output, "line  packet  message").unwrap();
+    for (index, datastream) in datastreams.iter().enumerate() {
+        let cells = first_markers(datastream, &[4, 14])
+            .into_iter()
+            .map(|first_marker| match first_marker {
+                Some(position) => position.to_string(),
+                None => "none".to_string(),
+            })
+            .collect::<Vec<String>>();
+        writeln!(output, "{:>4}  {:>6}  {:>7}", index + 1, cells[0], cells[1]).unwrap();
+    }
+    output
+}
+
+/// Generates a datastream of `size` characters (at least 14), which contains a start-of-message
+/// marker somewhere.
+pub fn tuning_trouble_generate(random: &mut Random, size: usize) -> String {
+    let size = size.max(14);
+    let mut letters = ('a'..='z').collect::<Vec<char>>();
+    // Mostly draw from a few letters, so that markers do not show up right away
+    let mut datastream = (0..size)
+        .map(|_| letters[random.below(6)])
+        .collect::<Vec<char>>();
+    random.shuffle(&mut letters);
+    let marker_start = random.below(size - 13);
+    datastream[marker_start..(marker_start + 14)].copy_from_slice(&letters[..14]);
+
+    let mut output = datastream.into_iter().collect::<String>();
+    output.push('\n');
+    output
 }
 
 #[cfg(test)]
@@ -158,4 +222,71 @@ mod tests {
         assert_eq!("26", output);
         Ok(())
     }
+
+    #[test]
+    fn missing_marker_should_fail() {
+        let error = tuning_trouble(PuzzleInput {
+            file_contents: "abcabcabc".to_string(),
+            puzzle_part: PuzzlePart::Part1,
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            "no marker of 4 different characters found\n",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn all_should_list_overlapping_markers() -> Result<(), Box<dyn Error>> {
+        let output = tuning_trouble_report(ReportInput::new("all", &["4"], "abcabcdaab"))?;
+
+        assert_eq!(
+            "\
+7: abcd
+8: bcda
+2 markers of 4 different characters
+",
+            output
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn first_should_report_missing_marker() -> Result<(), Box<dyn Error>> {
+        let found = tuning_trouble_report(ReportInput::new("first", &["14"], INPUT_TEXT_1))?;
+        let missing = tuning_trouble_report(ReportInput::new("first", &["4"], "aaaa"))?;
+
+        assert_eq!("19: qmgbljsphdztnv\n", found);
+        assert_eq!("no marker of 4 different characters found\n", missing);
+        Ok(())
+    }
+
+    #[test]
+    fn streams_should_find_both_markers_per_line() -> Result<(), Box<dyn Error>> {
+        let file_contents = [
+            INPUT_TEXT_1,
+            INPUT_TEXT_2,
+            INPUT_TEXT_3,
+            INPUT_TEXT_4,
+            INPUT_TEXT_5,
+            "abcdabcd",
+        ]
+        .join("\n");
+        let output = tuning_trouble_report(ReportInput::new("streams", &[], &file_contents))?;
+
+        assert_eq!(
+            "\
+line  packet  message
+   1       7       19
+   2       5       23
+   3       6       23
+   4      10       29
+   5      11       26
+   6       4     none
+",
+            output
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+pub enum MarkerError {
+    NotFound { length: usize },
+}
+
+impl Display for MarkerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound { length } => {
+                write!(f, "no marker of {} different characters found", length)
+            }
+        }
+    }
+}
+
+impl Debug for MarkerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <Self as Display>::fmt(self, f)
+    }
+}
+
+impl Error for MarkerError {}
+
+/// Tracks the longest run of different characters that ends at the latest character of a
+/// datastream, by remembering where every character was last seen.
+#[derive(Default)]
+pub struct DistinctRun {
+    last_seen: HashMap<char, usize>,
+    start: usize,
+    position: usize,
+}
+
+impl DistinctRun {
+    /// Adds the next character, and returns the length of the run of different characters that it
+    /// ends. A marker of any length up to that one ends at this character.
+    pub fn push(&mut self, c: char) -> usize {
+        if let Some(last_seen) = self.last_seen.insert(c, self.position) {
+            self.start = self.start.max(last_seen + 1);
+        }
+        self.position += 1;
+        self.position - self.start
+    }
+}
+
+/// Returns the number of characters read up to the end of every marker of the given length, in
+/// order. Markers can overlap.
+pub fn markers(datastream: &str, length: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut distinct_run = DistinctRun::default();
+    datastream
+        .chars()
+        .enumerate()
+        .filter(move |(_, c)| distinct_run.push(*c) >= length)
+        .map(|(index, _)| index + 1)
+}
+
+/// Returns, for each of the given lengths, the number of characters read up to the end of the
+/// first marker of that length, reading the datastream only once.
+pub fn first_markers(datastream: &str, lengths: &[usize]) -> Vec<Option<usize>> {
+    let mut first_markers = vec![None; lengths.len()];
+    let mut distinct_run = DistinctRun::default();
+    for (index, c) in datastream.chars().enumerate() {
+        let run_length = distinct_run.push(c);
+        for (first_marker, length) in first_markers.iter_mut().zip(lengths) {
+            if first_marker.is_none() && run_length >= *length {
+                *first_marker = Some(index + 1);
+            }
+        }
+        if first_markers
+            .iter()
+            .all(|first_marker| first_marker.is_some())
+        {
+            break;
+        }
+    }
+    first_markers
+}
+
+pub fn first_marker(datastream: &str, length: usize) -> Result<usize, MarkerError> {
+    markers(datastream, length)
+        .next()
+        .ok_or(MarkerError::NotFound { length })
+}
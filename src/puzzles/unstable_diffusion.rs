@@ -2,6 +2,7 @@ mod elf_diffuser;
 mod elf_distribution;
 
 use crate::{
+    common::random::Random,
     input::{puzzle_input::PuzzleInput, puzzle_part::PuzzlePart},
     parse::error::ParseContentsError,
     puzzles::unstable_diffusion::{elf_diffuser::AsDiffuser, elf_distribution::ElfDistribution},
@@ -32,6 +33,29 @@ pub fn unstable_diffusion(input: PuzzleInput) -> Result<String, ParseContentsErr
     Ok(answer.to_string())
 }
 
+/// Generates a `size` by `size` grove in which about two in five positions hold an elf, with at
+/// least one elf.
+pub fn unstable_diffusion_generate(random: &mut Random, size: usize) -> String {
+    let size = size.max(1);
+    let mut grove = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if random.chance(2, 5) { '#' } else { '.' })
+                .collect::<Vec<char>>()
+        })
+        .collect::<Vec<Vec<char>>>();
+    grove[random.below(size)][random.below(size)] = '#';
+
+    grove
+        .into_iter()
+        .map(|row| {
+            let mut line = row.into_iter().collect::<String>();
+            line.push('\n');
+            line
+        })
+        .collect::<String>()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;